@@ -0,0 +1,61 @@
+use criterion::{Criterion, criterion_group, criterion_main};
+use kapa::{Language, LoadOptions};
+use std::{env, fs, hint::black_box, path::PathBuf};
+
+// Enough files, each large enough to dominate thread start-up, for the
+// parallel loader to have something to spread out
+const FILES: usize = 8;
+const SIZE: usize = 20_000;
+
+fn write_datasets() -> Vec<PathBuf> {
+    let dir = env::temp_dir().join("kapa-bench-load");
+    fs::create_dir_all(&dir).unwrap();
+
+    (0..FILES)
+        .map(|file| {
+            let languages: Vec<_> = (0..SIZE)
+                .map(|i| Language {
+                    name: format!("Lang{}-{:05}", file, i),
+                    year: 1950 + (i % 75) as u32,
+                    creators: vec![format!("Creator {}", i % 500)],
+                    paradigm: vec!["imperative".to_string(), "functional".to_string()],
+                    typing: "static".to_string(),
+                    influenced_by: vec![format!("Lang{}-{:05}", file, i / 2)],
+                    aliases: Vec::new(),
+                    description: None,
+                    tags: Vec::new(),
+                })
+                .collect();
+            let path = dir.join(format!("languages-{}.json", file));
+            kapa::save_languages(&path, &languages).unwrap();
+            path
+        })
+        .collect()
+}
+
+fn load_files(c: &mut Criterion) {
+    let paths = write_datasets();
+    // Parse every time, so the benchmark measures loading rather than the cache
+    let options = LoadOptions {
+        cache: false,
+        ..Default::default()
+    };
+
+    let mut group = c.benchmark_group(format!("load {} files", FILES));
+    group.sample_size(10);
+    group.bench_function("serial", |b| {
+        b.iter(|| {
+            black_box(&paths)
+                .iter()
+                .map(|path| kapa::load_languages_with(path, &options).unwrap())
+                .collect::<Vec<_>>()
+        })
+    });
+    group.bench_function("parallel", |b| {
+        b.iter(|| kapa::load_languages_parallel(black_box(&paths), &options).unwrap())
+    });
+    group.finish();
+}
+
+criterion_group!(benches, load_files);
+criterion_main!(benches);
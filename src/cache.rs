@@ -0,0 +1,164 @@
+use crate::{DataFormat, Language};
+use serde::{Deserialize, Serialize};
+use std::{
+    fs,
+    hash::{DefaultHasher, Hash, Hasher},
+    path::{Path, PathBuf},
+    time::UNIX_EPOCH,
+};
+
+// Bumped whenever the stored layout changes, so older entries are never decoded
+const FORMAT: u32 = 4;
+
+// Entries are only reused when they were written by the same kapa version
+// from a source file with the same modification time and size.
+#[derive(Deserialize)]
+struct CacheEntry {
+    version: String,
+    modified: u128,
+    size: u64,
+    languages: Vec<CachedLanguage>,
+}
+
+#[derive(Serialize)]
+struct CacheEntryRef<'a> {
+    version: &'a str,
+    modified: u128,
+    size: u64,
+    languages: Vec<CachedLanguage>,
+}
+
+// bincode isn't self-describing, so every field is stored even where the
+// JSON form of `Language` skips empty ones
+#[derive(Serialize, Deserialize)]
+struct CachedLanguage {
+    name: String,
+    year: u32,
+    creators: Vec<String>,
+    paradigm: Vec<String>,
+    typing: String,
+    influenced_by: Vec<String>,
+    aliases: Vec<String>,
+    description: Option<String>,
+    tags: Vec<String>,
+}
+
+impl From<&Language> for CachedLanguage {
+    fn from(lang: &Language) -> Self {
+        let lang = lang.clone();
+        CachedLanguage {
+            name: lang.name,
+            year: lang.year,
+            creators: lang.creators,
+            paradigm: lang.paradigm,
+            typing: lang.typing,
+            influenced_by: lang.influenced_by,
+            aliases: lang.aliases,
+            description: lang.description,
+            tags: lang.tags,
+        }
+    }
+}
+
+impl From<CachedLanguage> for Language {
+    fn from(lang: CachedLanguage) -> Self {
+        Language {
+            name: lang.name,
+            year: lang.year,
+            creators: lang.creators,
+            paradigm: lang.paradigm,
+            typing: lang.typing,
+            influenced_by: lang.influenced_by,
+            aliases: lang.aliases,
+            description: lang.description,
+            tags: lang.tags,
+        }
+    }
+}
+
+/// Previously parsed languages for `source`, if the file hasn't changed since
+/// it was last parsed as `format`.
+pub(crate) fn read(source: &Path, format: DataFormat) -> Option<Vec<Language>> {
+    let (modified, size) = stamp(source)?;
+    let bytes = fs::read(cache_path(source, format)?).ok()?;
+    let (entry, _): (CacheEntry, _) =
+        bincode::serde::decode_from_slice(&bytes, bincode::config::standard()).ok()?;
+
+    (entry.version == env!("CARGO_PKG_VERSION") && entry.modified == modified && entry.size == size)
+        .then(|| entry.languages.into_iter().map(Language::from).collect())
+}
+
+/// Stores the languages parsed from `source` as `format`. Failures are
+/// ignored since the cache is only an optimization.
+pub(crate) fn write(source: &Path, format: DataFormat, languages: &[Language]) {
+    let (Some((modified, size)), Some(path)) = (stamp(source), cache_path(source, format)) else {
+        return;
+    };
+
+    let entry = CacheEntryRef {
+        version: env!("CARGO_PKG_VERSION"),
+        modified,
+        size,
+        languages: languages.iter().map(CachedLanguage::from).collect(),
+    };
+    let Ok(bytes) = bincode::serde::encode_to_vec(&entry, bincode::config::standard()) else {
+        return;
+    };
+
+    if let Some(dir) = path.parent() {
+        let _ = fs::create_dir_all(dir);
+    }
+    let _ = fs::write(path, bytes);
+}
+
+/// The copy of the dataset last downloaded from `url`, kept as-is so it can
+/// be parsed like a freshly fetched one.
+pub(crate) fn remote_path(url: &str) -> Option<PathBuf> {
+    let mut hasher = DefaultHasher::new();
+    url.hash(&mut hasher);
+
+    Some(
+        dirs::cache_dir()?
+            .join("kapa")
+            .join(format!("remote-{:016x}.json", hasher.finish())),
+    )
+}
+
+/// Keeps `body` as the copy of `url` to fall back on. Failures are ignored,
+/// like those of [`write`].
+pub(crate) fn write_remote(url: &str, body: &str) {
+    let Some(path) = remote_path(url) else {
+        return;
+    };
+
+    if let Some(dir) = path.parent() {
+        let _ = fs::create_dir_all(dir);
+    }
+    let _ = fs::write(path, body);
+}
+
+fn stamp(source: &Path) -> Option<(u128, u64)> {
+    let metadata = fs::metadata(source).ok()?;
+    let modified = metadata
+        .modified()
+        .ok()?
+        .duration_since(UNIX_EPOCH)
+        .ok()?
+        .as_nanos();
+    Some((modified, metadata.len()))
+}
+
+// The same file parsed as another format gives other languages, or none at all
+fn cache_path(source: &Path, format: DataFormat) -> Option<PathBuf> {
+    let source = fs::canonicalize(source).ok()?;
+    let mut hasher = DefaultHasher::new();
+    source.hash(&mut hasher);
+    format.hash(&mut hasher);
+    FORMAT.hash(&mut hasher);
+
+    Some(
+        dirs::cache_dir()?
+            .join("kapa")
+            .join(format!("{:016x}.bin", hasher.finish())),
+    )
+}
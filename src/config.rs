@@ -0,0 +1,64 @@
+use crate::{SortKey, output::OutputFormat};
+use serde::Deserialize;
+use std::{fmt, fs, io, path::PathBuf};
+
+/// Defaults read from `~/.config/kapa/config.toml`.
+///
+/// Every field is optional. Command-line flags override these values, and
+/// these values override the built-in defaults.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    pub format: Option<OutputFormat>,
+    pub data_file: Option<PathBuf>,
+    pub color: Option<bool>,
+    pub sort: Option<SortKey>,
+}
+
+#[derive(Debug)]
+pub enum ConfigError {
+    Read {
+        path: PathBuf,
+        source: io::Error,
+    },
+    Parse {
+        path: PathBuf,
+        source: toml::de::Error,
+    },
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigError::Read { path, source } => {
+                write!(f, "could not read {}: {}", path.display(), source)
+            }
+            ConfigError::Parse { path, source } => {
+                write!(f, "invalid config file {}: {}", path.display(), source)
+            }
+        }
+    }
+}
+
+pub fn config_path() -> Option<PathBuf> {
+    dirs::config_dir().map(|dir| dir.join("kapa").join("config.toml"))
+}
+
+/// Loads the config file, falling back to an empty config when it doesn't exist.
+pub fn load() -> Result<Config, ConfigError> {
+    let Some(path) = config_path() else {
+        return Ok(Config::default());
+    };
+
+    let contents = match fs::read_to_string(&path) {
+        Ok(contents) => contents,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => {
+            log::debug!("no config file at {}", path.display());
+            return Ok(Config::default());
+        }
+        Err(source) => return Err(ConfigError::Read { path, source }),
+    };
+
+    log::debug!("reading config from {}", path.display());
+    toml::from_str(&contents).map_err(|source| ConfigError::Parse { path, source })
+}
@@ -0,0 +1,95 @@
+use crate::Language;
+use std::collections::HashMap;
+
+/// Differences between two versions of a dataset, matching languages by name.
+#[derive(Debug, Clone, Default)]
+pub struct Diff<'a> {
+    pub added: Vec<&'a Language>,
+    pub removed: Vec<&'a Language>,
+    pub changed: Vec<Change<'a>>,
+}
+
+/// A language present in both versions whose fields differ.
+#[derive(Debug, Clone)]
+pub struct Change<'a> {
+    pub name: &'a str,
+    pub fields: Vec<FieldChange>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FieldChange {
+    pub field: &'static str,
+    pub old: String,
+    pub new: String,
+}
+
+impl Diff<'_> {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+}
+
+/// Compares `old` against `new`, matching names case-insensitively.
+pub fn diff<'a>(old: &'a [Language], new: &'a [Language]) -> Diff<'a> {
+    let old_by_name: HashMap<_, _> = old
+        .iter()
+        .map(|lang| (lang.name.to_lowercase(), lang))
+        .collect();
+    let new_by_name: HashMap<_, _> = new
+        .iter()
+        .map(|lang| (lang.name.to_lowercase(), lang))
+        .collect();
+
+    let mut diff = Diff::default();
+
+    for lang in old {
+        if !new_by_name.contains_key(&lang.name.to_lowercase()) {
+            diff.removed.push(lang);
+        }
+    }
+
+    for lang in new {
+        let Some(before) = old_by_name.get(&lang.name.to_lowercase()) else {
+            diff.added.push(lang);
+            continue;
+        };
+
+        let fields = field_changes(before, lang);
+        if !fields.is_empty() {
+            diff.changed.push(Change {
+                name: &lang.name,
+                fields,
+            });
+        }
+    }
+
+    diff
+}
+
+fn field_changes(old: &Language, new: &Language) -> Vec<FieldChange> {
+    let fields = [
+        ("name", old.name.clone(), new.name.clone()),
+        ("year", old.year.to_string(), new.year.to_string()),
+        ("creators", old.creators.join(", "), new.creators.join(", ")),
+        ("paradigm", old.paradigm.join(", "), new.paradigm.join(", ")),
+        ("typing", old.typing.clone(), new.typing.clone()),
+        (
+            "influenced_by",
+            old.influenced_by.join(", "),
+            new.influenced_by.join(", "),
+        ),
+        ("aliases", old.aliases.join(", "), new.aliases.join(", ")),
+        (
+            "description",
+            old.description.clone().unwrap_or_default(),
+            new.description.clone().unwrap_or_default(),
+        ),
+        ("tags", old.tags.join(", "), new.tags.join(", ")),
+    ];
+
+    fields
+        .into_iter()
+        .filter(|(_, old, new)| old != new)
+        .map(|(field, old, new)| FieldChange { field, old, new })
+        .collect()
+}
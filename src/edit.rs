@@ -0,0 +1,68 @@
+use crate::Language;
+use std::fmt;
+
+#[derive(Debug)]
+pub enum EditError {
+    EmptyName,
+    MissingYear,
+    Duplicate(String),
+    NotFound(String),
+    MultipleMatches { name: String, count: usize },
+}
+
+impl fmt::Display for EditError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EditError::EmptyName => write!(f, "Language name must not be empty"),
+            EditError::MissingYear => write!(f, "Language year must be greater than 0"),
+            EditError::Duplicate(name) => write!(f, "A language named '{}' already exists", name),
+            EditError::NotFound(name) => write!(f, "No language named '{}'", name),
+            EditError::MultipleMatches { name, count } => write!(
+                f,
+                "{} languages are named '{}', fix the dataset by hand",
+                count, name
+            ),
+        }
+    }
+}
+
+impl std::error::Error for EditError {}
+
+/// Appends `lang` to the dataset, refusing incomplete entries and names that
+/// already exist (ignoring case).
+pub fn add_language(languages: &mut Vec<Language>, lang: Language) -> Result<(), EditError> {
+    if lang.name.trim().is_empty() {
+        return Err(EditError::EmptyName);
+    }
+    if lang.year == 0 {
+        return Err(EditError::MissingYear);
+    }
+    if let Some(existing) = languages
+        .iter()
+        .find(|existing| existing.name.to_lowercase() == lang.name.to_lowercase())
+    {
+        return Err(EditError::Duplicate(existing.name.clone()));
+    }
+
+    languages.push(lang);
+    Ok(())
+}
+
+/// Removes the one language named `name` (ignoring case) from the dataset.
+pub fn remove_language(languages: &mut Vec<Language>, name: &str) -> Result<Language, EditError> {
+    let matches: Vec<_> = languages
+        .iter()
+        .enumerate()
+        .filter(|(_, lang)| lang.name.to_lowercase() == name.to_lowercase())
+        .map(|(index, _)| index)
+        .collect();
+
+    match matches.as_slice() {
+        [] => Err(EditError::NotFound(name.to_string())),
+        [index] => Ok(languages.remove(*index)),
+        _ => Err(EditError::MultipleMatches {
+            name: name.to_string(),
+            count: matches.len(),
+        }),
+    }
+}
@@ -0,0 +1,53 @@
+use crate::Language;
+use std::ops::RangeInclusive;
+
+/// A set of optional criteria; a language matches when it satisfies all of
+/// the criteria that are set.
+#[derive(Debug, Clone, Default)]
+pub struct Filter {
+    /// Substring of the language name, ignoring case.
+    pub name: Option<String>,
+    /// Inclusive range of creation years.
+    pub years: Option<RangeInclusive<u32>>,
+    /// Substrings of creators' names, ignoring case; every one must match
+    /// some creator.
+    pub creators: Vec<String>,
+    /// Substring of any paradigm, ignoring case.
+    pub paradigm: Option<String>,
+    /// Substring of the typing discipline, ignoring case.
+    pub typing: Option<String>,
+}
+
+impl Filter {
+    pub fn matches(&self, lang: &Language) -> bool {
+        self.name
+            .as_ref()
+            .is_none_or(|name| contains_ignore_case(&lang.name, name))
+            && self
+                .years
+                .as_ref()
+                .is_none_or(|years| years.contains(&lang.year))
+            && self.creators.iter().all(|creator| {
+                lang.creators
+                    .iter()
+                    .any(|c| contains_ignore_case(c, creator))
+            })
+            && self.paradigm.as_ref().is_none_or(|paradigm| {
+                lang.paradigm
+                    .iter()
+                    .any(|p| contains_ignore_case(p, paradigm))
+            })
+            && self
+                .typing
+                .as_ref()
+                .is_none_or(|typing| contains_ignore_case(&lang.typing, typing))
+    }
+
+    pub fn apply<'a>(&self, languages: &'a [Language]) -> Vec<&'a Language> {
+        languages.iter().filter(|lang| self.matches(lang)).collect()
+    }
+}
+
+fn contains_ignore_case(haystack: &str, needle: &str) -> bool {
+    haystack.to_lowercase().contains(&needle.to_lowercase())
+}
@@ -0,0 +1,275 @@
+use crate::Language;
+use std::collections::{BTreeMap, HashMap};
+
+/// Directed graph of influence relationships, with edges pointing from the
+/// influencing language to the language it influenced.
+#[derive(Debug, Clone)]
+pub struct Graph<'a> {
+    pub languages: Vec<&'a Language>,
+    pub edges: Vec<(usize, usize)>,
+    pub unresolved: Vec<(&'a str, &'a str)>,
+}
+
+impl<'a> Graph<'a> {
+    /// Builds the graph, collecting `influenced_by` entries that name no known language.
+    pub fn build(languages: &'a [Language]) -> Self {
+        let index: HashMap<_, _> = languages
+            .iter()
+            .enumerate()
+            .map(|(i, lang)| (lang.name.to_lowercase(), i))
+            .collect();
+
+        let mut edges = Vec::new();
+        let mut unresolved = Vec::new();
+
+        for (to, lang) in languages.iter().enumerate() {
+            for influence in &lang.influenced_by {
+                match index.get(&influence.to_lowercase()) {
+                    Some(&from) => edges.push((from, to)),
+                    None => unresolved.push((lang.name.as_str(), influence.as_str())),
+                }
+            }
+        }
+
+        Graph {
+            languages: languages.iter().collect(),
+            edges,
+            unresolved,
+        }
+    }
+
+    /// Returns each influence cycle found, as the names along the cycle in edge order.
+    pub fn cycles(&self) -> Vec<Vec<&'a str>> {
+        let mut adjacent = vec![Vec::new(); self.languages.len()];
+        for &(from, to) in &self.edges {
+            adjacent[from].push(to);
+        }
+
+        // 0 = unvisited, 1 = on the current path, 2 = finished
+        let mut state = vec![0u8; self.languages.len()];
+        let mut path = Vec::new();
+        let mut cycles = Vec::new();
+
+        // An explicit stack rather than recursion, so long influence chains
+        // can't overflow the call stack. Each entry is a node on the current
+        // path and the index of the next edge to follow from it.
+        let mut stack: Vec<(usize, usize)> = Vec::new();
+
+        for start in 0..self.languages.len() {
+            if state[start] != 0 {
+                continue;
+            }
+            state[start] = 1;
+            path.push(start);
+            stack.push((start, 0));
+
+            while let Some((node, edge)) = stack.last_mut() {
+                let node = *node;
+                let Some(&next) = adjacent[node].get(*edge) else {
+                    stack.pop();
+                    path.pop();
+                    state[node] = 2;
+                    continue;
+                };
+                *edge += 1;
+
+                match state[next] {
+                    0 => {
+                        state[next] = 1;
+                        path.push(next);
+                        stack.push((next, 0));
+                    }
+                    1 => {
+                        let start = path.iter().position(|&n| n == next).unwrap();
+                        cycles.push(
+                            path[start..]
+                                .iter()
+                                .map(|&n| self.languages[n].name.as_str())
+                                .collect(),
+                        );
+                    }
+                    _ => {}
+                }
+            }
+        }
+
+        cycles
+    }
+
+    /// Each language's name with the names of the known languages it was
+    /// influenced by, in `influenced_by` order. Unresolved names are left out.
+    pub fn adjacency(&self) -> BTreeMap<&'a str, Vec<&'a str>> {
+        let mut adjacency: BTreeMap<_, _> = self
+            .languages
+            .iter()
+            .map(|lang| (lang.name.as_str(), Vec::new()))
+            .collect();
+        for &(from, to) in &self.edges {
+            if let Some(parents) = adjacency.get_mut(self.languages[to].name.as_str()) {
+                parents.push(self.languages[from].name.as_str());
+            }
+        }
+        adjacency
+    }
+
+    /// Renders the graph in Graphviz DOT format.
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::from("digraph influences {\n");
+
+        for lang in &self.languages {
+            dot.push_str(&format!("    {};\n", quote(&lang.name)));
+        }
+        for &(from, to) in &self.edges {
+            dot.push_str(&format!(
+                "    {} -> {};\n",
+                quote(&self.languages[from].name),
+                quote(&self.languages[to].name)
+            ));
+        }
+
+        dot.push_str("}\n");
+        dot
+    }
+}
+
+/// A language and the languages reached from it by following influences.
+#[derive(Debug, Clone)]
+pub struct Tree<'a> {
+    pub name: &'a str,
+    pub mark: Mark,
+    pub children: Vec<Tree<'a>>,
+}
+
+/// Why a [`Tree`] node has no children of its own.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Mark {
+    /// Expanded in full at this point in the tree.
+    None,
+    /// Named in an `influenced_by` list, but not in the dataset.
+    Unknown,
+    /// Already expanded earlier in the tree.
+    Repeated,
+    /// Leads back to a language on the current path.
+    Cycle,
+    /// Too deep in the tree to expand.
+    Truncated,
+}
+
+// Levels expanded before the remaining branches are marked truncated.
+// Building, printing and dropping a tree all recurse once per level, so this
+// keeps long influence chains from overflowing the stack.
+const MAX_DEPTH: usize = 500;
+
+/// The languages `root` was influenced by, recursively.
+///
+/// `root` must be one of `languages`.
+pub fn ancestry<'a>(languages: &'a [Language], root: &Language) -> Tree<'a> {
+    let index = name_index(languages);
+    let parents: Vec<Vec<_>> = languages
+        .iter()
+        .map(|lang| {
+            lang.influenced_by
+                .iter()
+                .map(|influence| {
+                    index
+                        .get(&influence.to_lowercase())
+                        .copied()
+                        .ok_or(influence.as_str())
+                })
+                .collect()
+        })
+        .collect();
+
+    let root = position(languages, root);
+    walk(
+        languages,
+        &parents,
+        root,
+        &mut Vec::new(),
+        &mut vec![false; languages.len()],
+    )
+}
+
+/// The languages influenced by `root`, recursively.
+///
+/// `root` must be one of `languages`.
+pub fn descendants<'a>(languages: &'a [Language], root: &Language) -> Tree<'a> {
+    let index = name_index(languages);
+    let mut children = vec![Vec::new(); languages.len()];
+    for (i, lang) in languages.iter().enumerate() {
+        for influence in &lang.influenced_by {
+            if let Some(&parent) = index.get(&influence.to_lowercase()) {
+                children[parent].push(Ok(i));
+            }
+        }
+    }
+
+    let root = position(languages, root);
+    walk(
+        languages,
+        &children,
+        root,
+        &mut Vec::new(),
+        &mut vec![false; languages.len()],
+    )
+}
+
+// By identity rather than name, so a root sharing its name with another
+// language isn't swapped for it
+fn position(languages: &[Language], root: &Language) -> usize {
+    languages
+        .iter()
+        .position(|lang| std::ptr::eq(lang, root))
+        .expect("root is one of the languages")
+}
+
+fn name_index(languages: &[Language]) -> HashMap<String, usize> {
+    languages
+        .iter()
+        .enumerate()
+        .map(|(i, lang)| (lang.name.to_lowercase(), i))
+        .collect()
+}
+
+// `next` lists, per language, the languages one step further along; unknown
+// names are kept as errors so they can be shown as leaves
+fn walk<'a>(
+    languages: &'a [Language],
+    next: &[Vec<Result<usize, &'a str>>],
+    node: usize,
+    path: &mut Vec<usize>,
+    expanded: &mut [bool],
+) -> Tree<'a> {
+    expanded[node] = true;
+    path.push(node);
+
+    let children = next[node]
+        .iter()
+        .map(|&target| match target {
+            Err(name) => leaf(name, Mark::Unknown),
+            Ok(i) if path.contains(&i) => leaf(&languages[i].name, Mark::Cycle),
+            Ok(i) if expanded[i] => leaf(&languages[i].name, Mark::Repeated),
+            Ok(i) if path.len() >= MAX_DEPTH => leaf(&languages[i].name, Mark::Truncated),
+            Ok(i) => walk(languages, next, i, path, expanded),
+        })
+        .collect();
+
+    path.pop();
+    Tree {
+        name: &languages[node].name,
+        mark: Mark::None,
+        children,
+    }
+}
+
+fn leaf(name: &str, mark: Mark) -> Tree<'_> {
+    Tree {
+        name,
+        mark,
+        children: Vec::new(),
+    }
+}
+
+fn quote(name: &str) -> String {
+    format!("\"{}\"", name.replace('\\', "\\\\").replace('"', "\\\""))
+}
@@ -0,0 +1,1253 @@
+use flate2::{Compression, read::GzDecoder, write::GzEncoder};
+use globset::GlobBuilder;
+use log::debug;
+use rand::{SeedableRng, rngs::StdRng, seq::IndexedRandom};
+use rayon::prelude::*;
+use regex::RegexBuilder;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::{
+    cmp::Reverse,
+    collections::{BTreeMap, HashMap},
+    env, fmt, fs,
+    io::{self, Read, Write},
+    ops::RangeInclusive,
+    path::{Path, PathBuf},
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+
+mod cache;
+mod diff;
+mod edit;
+mod filter;
+mod graph;
+mod validate;
+
+pub use diff::{Change, Diff, FieldChange, diff};
+pub use edit::{EditError, add_language, remove_language};
+pub use filter::Filter;
+pub use graph::{Graph, Mark, Tree, ancestry, descendants};
+pub use validate::{Problem, validate};
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Language {
+    pub name: String,
+    pub year: u32,
+    pub creators: Vec<String>,
+    pub paradigm: Vec<String>,
+    pub typing: String,
+    pub influenced_by: Vec<String>,
+    /// Alternate names the language is known by, such as "JS" for JavaScript.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub aliases: Vec<String>,
+    /// A sentence or two about the language.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// Free-form categories such as "systems" or "scripting".
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+}
+
+// Keys a dataset entry may have, which must match the fields of `Language`
+const FIELDS: [&str; 9] = [
+    "name",
+    "year",
+    "creators",
+    "paradigm",
+    "typing",
+    "influenced_by",
+    "aliases",
+    "description",
+    "tags",
+];
+
+#[derive(Debug)]
+pub enum LoadError {
+    NotFound {
+        searched: Vec<PathBuf>,
+    },
+    Read {
+        path: PathBuf,
+        source: io::Error,
+    },
+    Fetch {
+        url: String,
+        source: reqwest::Error,
+    },
+    Parse(serde_json::Error),
+    ParseYaml(serde_yaml::Error),
+    /// `line` and `column` are where the parser gave up, as serde reports them.
+    NotArray {
+        found: &'static str,
+        line: usize,
+        column: usize,
+    },
+    /// `line` and `column` locate the problem within the entry, as serde reports them.
+    Entry {
+        index: usize,
+        name: Option<String>,
+        line: usize,
+        column: usize,
+        source: serde_json::Error,
+    },
+    UnknownField {
+        index: usize,
+        /// Set for newline-delimited JSON, where the line, counted from 1,
+        /// is easier to find than the index.
+        line: Option<usize>,
+        name: Option<String>,
+        field: String,
+    },
+    /// A line of a newline-delimited JSON dataset, counted from 1.
+    Line {
+        line: usize,
+        source: serde_json::Error,
+    },
+}
+
+impl fmt::Display for LoadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LoadError::NotFound { searched } => {
+                let searched_paths = searched
+                    .iter()
+                    .map(|p| p.display().to_string())
+                    .collect::<Vec<_>>()
+                    .join("\n- ");
+                write!(
+                    f,
+                    "Could not find languages.json in any of these locations:\n- {}\n\n\
+                    Please ensure the data file exists in one of these paths",
+                    searched_paths
+                )
+            }
+            LoadError::Read { path, source } => {
+                write!(f, "Could not read {}: {}", path.display(), source)
+            }
+            LoadError::Fetch { url, source } => write!(f, "Could not fetch {}: {}", url, source),
+            LoadError::Parse(err) => write!(f, "Failed to parse JSON data: {}", err),
+            LoadError::ParseYaml(err) => write!(f, "Failed to parse YAML data: {}", err),
+            LoadError::NotArray {
+                found,
+                line,
+                column,
+            } => write!(
+                f,
+                "Expected the data file to contain a JSON array of languages, found {} \
+                 at line {} column {}",
+                found, line, column
+            ),
+            LoadError::Entry {
+                index,
+                name,
+                line,
+                column,
+                source,
+            } => {
+                write!(f, "Invalid language at index {}", index)?;
+                if let Some(name) = name {
+                    write!(f, " ({})", name)?;
+                }
+                write!(f, " at line {} column {}: {}", line, column, source)
+            }
+            LoadError::Line { line, source } => {
+                write!(f, "Invalid language on line {}: {}", line, source)
+            }
+            LoadError::UnknownField {
+                index,
+                line,
+                name,
+                field,
+            } => {
+                write!(f, "Unknown field `{}` in language ", field)?;
+                match line {
+                    Some(line) => write!(f, "on line {}", line)?,
+                    None => write!(f, "at index {}", index)?,
+                }
+                if let Some(name) = name {
+                    write!(f, " ({})", name)?;
+                }
+                write!(f, "; expected one of {}", FIELDS.join(", "))
+            }
+        }
+    }
+}
+
+impl std::error::Error for LoadError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            LoadError::NotFound { .. }
+            | LoadError::NotArray { .. }
+            | LoadError::UnknownField { .. } => None,
+            LoadError::Read { source, .. } => Some(source),
+            LoadError::Fetch { source, .. } => Some(source),
+            LoadError::Parse(err) => Some(err),
+            LoadError::ParseYaml(err) => Some(err),
+            LoadError::Entry { source, .. } => Some(source),
+            LoadError::Line { source, .. } => Some(source),
+        }
+    }
+}
+
+impl From<serde_json::Error> for LoadError {
+    fn from(err: serde_json::Error) -> Self {
+        LoadError::Parse(err)
+    }
+}
+
+#[derive(Debug)]
+pub enum LookupError {
+    NotFound(String),
+    Ambiguous {
+        query: String,
+        candidates: Vec<String>,
+    },
+}
+
+impl fmt::Display for LookupError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LookupError::NotFound(query) => write!(f, "No language found matching '{}'", query),
+            LookupError::Ambiguous { query, candidates } => write!(
+                f,
+                "'{}' is ambiguous, did you mean one of: {}?",
+                query,
+                candidates.join(", ")
+            ),
+        }
+    }
+}
+
+impl std::error::Error for LookupError {}
+
+/// The dataset bundled into the binary, used when no data file can be found.
+pub const EMBEDDED_DATA: &str = include_str!("../data/languages.json");
+
+/// Loads the dataset from the first `languages.json` found in the standard
+/// locations, falling back to the embedded copy when there is none.
+pub fn load_languages() -> Result<Vec<Language>, LoadError> {
+    match find_data_file() {
+        Ok(path) => load_languages_from(&path),
+        Err(LoadError::NotFound { .. }) => embedded_languages(),
+        Err(err) => Err(err),
+    }
+}
+
+/// Parses the dataset embedded in the binary.
+pub fn embedded_languages() -> Result<Vec<Language>, LoadError> {
+    parse_languages(EMBEDDED_DATA)
+}
+
+fn parse_languages(data: &str) -> Result<Vec<Language>, LoadError> {
+    serde_json::from_str(data).map_err(|err| diagnose(data, err))
+}
+
+// Only runs once parsing has already failed, to explain what is wrong in
+// terms of the dataset rather than serde's view of it
+fn diagnose(data: &str, err: serde_json::Error) -> LoadError {
+    let entries = match serde_json::from_str(data) {
+        Ok(Value::Array(entries)) => entries,
+        Ok(value) => {
+            let found = match value {
+                Value::Object(_) => "an object",
+                Value::String(_) => "a string",
+                Value::Number(_) => "a number",
+                Value::Bool(_) => "a boolean",
+                _ => "null",
+            };
+            return LoadError::NotArray {
+                found,
+                line: err.line(),
+                column: err.column(),
+            };
+        }
+        // Not valid JSON at all, so serde's error already has the line and column
+        Err(_) => return LoadError::Parse(err),
+    };
+
+    // Values carry no position, but serde stopped at the first bad entry, which
+    // is the one found here, so its error has the location
+    for (index, entry) in entries.into_iter().enumerate() {
+        let name = entry
+            .get("name")
+            .and_then(Value::as_str)
+            .map(str::to_string);
+        if let Err(source) = Language::deserialize(entry) {
+            return LoadError::Entry {
+                index,
+                name,
+                line: err.line(),
+                column: err.column(),
+                source,
+            };
+        }
+    }
+
+    LoadError::Parse(err)
+}
+
+/// Path of the first `languages.json` found in the standard locations.
+pub fn find_data_file() -> Result<PathBuf, LoadError> {
+    let paths = data_file_candidates();
+    for path in &paths {
+        if path.is_file() {
+            debug!("checking {}: found", path.display());
+            return Ok(path.clone());
+        }
+        debug!("checking {}: not found", path.display());
+    }
+
+    Err(LoadError::NotFound { searched: paths })
+}
+
+/// The standard locations searched for `languages.json`, in order.
+pub fn data_file_candidates() -> Vec<PathBuf> {
+    // Try multiple possible locations for the data file
+    let mut paths = vec![
+        // Development location
+        PathBuf::from("languages.json"),
+    ];
+    // Next to executable
+    if let Some(dir) = env::current_exe()
+        .ok()
+        .and_then(|exe| exe.parent().map(PathBuf::from))
+    {
+        paths.push(dir.join("languages.json"));
+    }
+    // System data directory
+    paths.push(PathBuf::from("/usr/local/share/kapa/languages.json"));
+    // User data directory
+    if let Some(path) = user_data_file() {
+        paths.push(path);
+    }
+    paths
+}
+
+/// Where `languages.json` lives in the user's data directory, if there is one.
+pub fn user_data_file() -> Option<PathBuf> {
+    dirs::data_local_dir().map(|dir| dir.join("kapa/languages.json"))
+}
+
+/// Controls how a dataset file is loaded.
+#[derive(Debug, Clone, Default)]
+pub struct LoadOptions {
+    /// Reuse a previously parsed copy of the file when it hasn't changed.
+    pub cache: bool,
+    /// Reject entries with keys that aren't fields of [`Language`], instead
+    /// of ignoring them.
+    pub strict: bool,
+    /// Parse files in this format instead of going by their extension.
+    pub format: Option<DataFormat>,
+}
+
+/// The syntax of a dataset file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DataFormat {
+    /// A JSON array of languages.
+    Json,
+    /// A YAML sequence of languages.
+    Yaml,
+    /// One JSON language object per line.
+    Ndjson,
+}
+
+impl DataFormat {
+    /// The format `path`'s extension names, defaulting to JSON. A `.gz`
+    /// extension is looked past, so `languages.yaml.gz` is YAML.
+    pub fn from_path(path: &Path) -> Self {
+        let path = match path.file_stem() {
+            Some(stem) if is_gzip(path) => Path::new(stem),
+            _ => path,
+        };
+        let extension = path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .map(str::to_lowercase);
+        match extension.as_deref() {
+            Some("yaml" | "yml") => DataFormat::Yaml,
+            Some("ndjson" | "jsonl") => DataFormat::Ndjson,
+            _ => DataFormat::Json,
+        }
+    }
+}
+
+/// Loads the dataset from `path`, without searching any other location.
+///
+/// A path of `-` reads the dataset from stdin.
+pub fn load_languages_from(path: &Path) -> Result<Vec<Language>, LoadError> {
+    load_languages_with(path, &LoadOptions::default())
+}
+
+/// Like [`load_languages_from`], with explicit [`LoadOptions`].
+pub fn load_languages_with(path: &Path, options: &LoadOptions) -> Result<Vec<Language>, LoadError> {
+    let stdin = path == Path::new("-");
+    // A cached copy says nothing about the keys in the file
+    let cache = options.cache && !stdin && !options.strict;
+    let format = options
+        .format
+        .unwrap_or_else(|| DataFormat::from_path(path));
+    let started = Instant::now();
+    if cache && let Some(languages) = cache::read(path, format) {
+        debug!(
+            "loaded {} languages from the cache in {:.2?}",
+            languages.len(),
+            started.elapsed()
+        );
+        return Ok(languages);
+    }
+
+    let data = if stdin {
+        read_data(io::stdin(), Path::new("<stdin>"))?
+    } else {
+        let file = fs::File::open(path).map_err(|source| LoadError::Read {
+            path: path.to_path_buf(),
+            source,
+        })?;
+        read_data(file, path)?
+    };
+
+    let languages = match format {
+        DataFormat::Json => parse_languages(&data)?,
+        DataFormat::Yaml => serde_yaml::from_str(&data).map_err(LoadError::ParseYaml)?,
+        DataFormat::Ndjson => parse_lines(&data)?,
+    };
+    if options.strict {
+        match format {
+            DataFormat::Json => check_fields(&serde_json::from_str::<Vec<Value>>(&data)?, None)?,
+            DataFormat::Yaml => check_fields(
+                &serde_yaml::from_str::<Vec<Value>>(&data).map_err(LoadError::ParseYaml)?,
+                None,
+            )?,
+            DataFormat::Ndjson => {
+                let lines: Vec<_> = data_lines(&data).map(|(line, _)| line).collect();
+                check_fields(&parse_lines::<Value>(&data)?, Some(&lines))?;
+            }
+        }
+    }
+    debug!(
+        "parsed {} languages in {:.2?}",
+        languages.len(),
+        started.elapsed()
+    );
+    if cache {
+        cache::write(path, format, &languages);
+    }
+    Ok(languages)
+}
+
+// Blank lines are skipped, but still counted so errors point at the right line
+fn data_lines(data: &str) -> impl Iterator<Item = (usize, &str)> {
+    data.lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(i, line)| (i + 1, line))
+}
+
+fn parse_lines<T: serde::de::DeserializeOwned>(data: &str) -> Result<Vec<T>, LoadError> {
+    data_lines(data)
+        .map(|(line, text)| {
+            serde_json::from_str(text).map_err(|source| LoadError::Line { line, source })
+        })
+        .collect()
+}
+
+// Only called once the entries have parsed as languages, so each one is an
+// object. `lines` holds the line of each entry, for newline-delimited files.
+fn check_fields(entries: &[Value], lines: Option<&[usize]>) -> Result<(), LoadError> {
+    for (index, entry) in entries.iter().enumerate() {
+        let Some(entry) = entry.as_object() else {
+            continue;
+        };
+        if let Some(field) = entry.keys().find(|key| !FIELDS.contains(&key.as_str())) {
+            return Err(LoadError::UnknownField {
+                index,
+                line: lines.map(|lines| lines[index]),
+                name: entry
+                    .get("name")
+                    .and_then(Value::as_str)
+                    .map(str::to_string),
+                field: field.clone(),
+            });
+        }
+    }
+    Ok(())
+}
+
+/// Loads each of `paths` like [`load_languages_with`], reading and parsing
+/// the files in parallel.
+///
+/// The datasets come back in the same order as `paths`. When any file fails
+/// to load, every failure is returned instead, paired with its path.
+pub fn load_languages_parallel(
+    paths: &[PathBuf],
+    options: &LoadOptions,
+) -> Result<Vec<Vec<Language>>, Vec<(PathBuf, LoadError)>> {
+    load_languages_parallel_with_progress(paths, options, || {})
+}
+
+/// Like [`load_languages_parallel`], calling `loaded` as each file finishes
+/// loading, whether or not it succeeded.
+pub fn load_languages_parallel_with_progress(
+    paths: &[PathBuf],
+    options: &LoadOptions,
+    loaded: impl Fn() + Sync,
+) -> Result<Vec<Vec<Language>>, Vec<(PathBuf, LoadError)>> {
+    let results: Vec<_> = paths
+        .par_iter()
+        .map(|path| {
+            let result = load_languages_with(path, options);
+            loaded();
+            result
+        })
+        .collect();
+
+    let mut datasets = Vec::new();
+    let mut errors = Vec::new();
+    for (path, result) in paths.iter().zip(results) {
+        match result {
+            Ok(languages) => datasets.push(languages),
+            Err(err) => errors.push((path.clone(), err)),
+        }
+    }
+
+    if errors.is_empty() {
+        Ok(datasets)
+    } else {
+        Err(errors)
+    }
+}
+
+/// Downloads and parses the JSON dataset at `url`, keeping a copy that
+/// [`load_cached_url`] can fall back on when a later download fails.
+pub fn load_languages_from_url(url: &str) -> Result<Vec<Language>, LoadError> {
+    let fetch_error = |source| LoadError::Fetch {
+        url: url.to_string(),
+        source,
+    };
+
+    let started = Instant::now();
+    let client = reqwest::blocking::Client::builder()
+        .timeout(Duration::from_secs(10))
+        .build()
+        .map_err(fetch_error)?;
+    let body = client
+        .get(url)
+        .send()
+        .and_then(|response| response.error_for_status())
+        .and_then(|response| response.text())
+        .map_err(fetch_error)?;
+    debug!("fetched {} in {:.2?}", url, started.elapsed());
+
+    let languages = parse_languages(&body)?;
+    cache::write_remote(url, &body);
+    Ok(languages)
+}
+
+/// Parses the copy of `url` saved by the last successful
+/// [`load_languages_from_url`].
+pub fn load_cached_url(url: &str) -> Result<Vec<Language>, LoadError> {
+    let path = cache::remote_path(url).ok_or_else(|| LoadError::NotFound {
+        searched: Vec::new(),
+    })?;
+    debug!("reading the cached copy of {} from {}", url, path.display());
+
+    let data = fs::read_to_string(&path).map_err(|source| LoadError::Read { path, source })?;
+    parse_languages(&data)
+}
+
+/// Appends `additions` to `languages`, replacing in place any language
+/// already in `languages` with the same name (ignoring case).
+///
+/// Duplicates within `additions` are all kept, so `validate` can still
+/// report them. Returns the names of the languages that were replaced.
+pub fn merge_languages(languages: &mut Vec<Language>, additions: Vec<Language>) -> Vec<String> {
+    // Only names from earlier datasets can be overridden
+    let index: HashMap<_, _> = languages
+        .iter()
+        .enumerate()
+        .map(|(i, lang)| (lang.name.to_lowercase(), i))
+        .collect();
+
+    let mut replaced = Vec::new();
+    for lang in additions {
+        match index.get(&lang.name.to_lowercase()) {
+            Some(&i) => {
+                replaced.push(lang.name.clone());
+                languages[i] = lang;
+            }
+            None => languages.push(lang),
+        }
+    }
+    replaced
+}
+
+/// Resolves `name` to a single language.
+///
+/// An exact (case-insensitive) name match always wins; otherwise the name must
+/// be a substring of exactly one language's name.
+pub fn find<'a>(languages: &'a [Language], name: &str) -> Result<&'a Language, LookupError> {
+    if let Some(lang) = languages
+        .iter()
+        .find(|lang| lang.name.to_lowercase() == name.to_lowercase())
+    {
+        return Ok(lang);
+    }
+
+    match search(languages, name).as_slice() {
+        [] => Err(LookupError::NotFound(name.to_string())),
+        [lang] => Ok(lang),
+        candidates => Err(LookupError::Ambiguous {
+            query: name.to_string(),
+            candidates: candidates.iter().map(|lang| lang.name.clone()).collect(),
+        }),
+    }
+}
+
+/// Resolves `name` to the language with exactly that name, ignoring case.
+///
+/// Unlike [`find`], a partial name is never accepted; languages whose names
+/// contain it are reported as candidates instead.
+pub fn find_exact<'a>(languages: &'a [Language], name: &str) -> Result<&'a Language, LookupError> {
+    if let Some(lang) = languages
+        .iter()
+        .find(|lang| lang.name.to_lowercase() == name.to_lowercase())
+    {
+        return Ok(lang);
+    }
+
+    match search(languages, name).as_slice() {
+        [] => Err(LookupError::NotFound(name.to_string())),
+        candidates => Err(LookupError::Ambiguous {
+            query: name.to_string(),
+            candidates: candidates.iter().map(|lang| lang.name.clone()).collect(),
+        }),
+    }
+}
+
+/// The language that lists `alias` among its aliases, ignoring case.
+pub fn resolve_alias<'a>(languages: &'a [Language], alias: &str) -> Option<&'a Language> {
+    languages.iter().find(|lang| {
+        lang.aliases
+            .iter()
+            .any(|known| known.to_lowercase() == alias.to_lowercase())
+    })
+}
+
+/// Controls how the text filters compare names.
+#[derive(Debug, Clone, Default)]
+pub struct MatchOptions {
+    /// Compare letters exactly instead of ignoring case.
+    pub case_sensitive: bool,
+    /// Require the whole text to equal the query instead of containing it.
+    pub exact: bool,
+}
+
+impl MatchOptions {
+    fn matches(&self, text: &str, query: &str) -> bool {
+        match (self.case_sensitive, self.exact) {
+            (true, true) => text == query,
+            (true, false) => text.contains(query),
+            (false, true) => text.to_lowercase() == query.to_lowercase(),
+            (false, false) => text.to_lowercase().contains(&query.to_lowercase()),
+        }
+    }
+}
+
+/// Languages whose name contains `query`, ignoring case.
+pub fn search<'a>(languages: &'a [Language], query: &str) -> Vec<&'a Language> {
+    search_with(languages, query, &MatchOptions::default())
+}
+
+/// Like [`search`], with explicit [`MatchOptions`].
+pub fn search_with<'a>(
+    languages: &'a [Language],
+    query: &str,
+    options: &MatchOptions,
+) -> Vec<&'a Language> {
+    languages
+        .iter()
+        .filter(|lang| options.matches(&lang.name, query))
+        .collect()
+}
+
+// Gzip-compressed data is recognized by its magic bytes rather than the file
+// name, so a compressed dataset can also be piped in on stdin
+fn read_data(mut reader: impl Read, path: &Path) -> Result<String, LoadError> {
+    const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+
+    let read_error = |source| LoadError::Read {
+        path: path.to_path_buf(),
+        source,
+    };
+
+    let mut bytes = Vec::new();
+    reader.read_to_end(&mut bytes).map_err(read_error)?;
+
+    if bytes.starts_with(&GZIP_MAGIC) {
+        debug!("decompressing gzip data from {}", path.display());
+        let mut data = String::new();
+        GzDecoder::new(bytes.as_slice())
+            .read_to_string(&mut data)
+            .map_err(read_error)?;
+        return Ok(data);
+    }
+
+    String::from_utf8(bytes)
+        .map_err(|err| read_error(io::Error::new(io::ErrorKind::InvalidData, err)))
+}
+
+/// Whether `path` has a `.gz` extension, in which case it is compressed when saved.
+fn is_gzip(path: &Path) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("gz"))
+}
+
+/// Writes the dataset to `path` in the format its extension names (see
+/// [`DataFormat::from_path`]), with JSON pretty-printed. Paths ending in
+/// `.gz` are gzip-compressed.
+pub fn save_languages(path: &Path, languages: &[Language]) -> io::Result<()> {
+    save_languages_as(path, DataFormat::from_path(path), languages)
+}
+
+/// Like [`save_languages`], writing `format` whatever the extension says.
+pub fn save_languages_as(
+    path: &Path,
+    format: DataFormat,
+    languages: &[Language],
+) -> io::Result<()> {
+    let data = match format {
+        DataFormat::Yaml => serde_yaml::to_string(languages)
+            .map_err(io::Error::other)?
+            .into_bytes(),
+        DataFormat::Json => {
+            let mut data = Vec::new();
+            let formatter = serde_json::ser::PrettyFormatter::with_indent(b"    ");
+            let mut serializer = serde_json::Serializer::with_formatter(&mut data, formatter);
+            languages.serialize(&mut serializer)?;
+            data.push(b'\n');
+            data
+        }
+        DataFormat::Ndjson => {
+            let mut data = Vec::new();
+            for lang in languages {
+                serde_json::to_writer(&mut data, lang)?;
+                data.push(b'\n');
+            }
+            data
+        }
+    };
+
+    if is_gzip(path) {
+        let mut encoder = GzEncoder::new(fs::File::create(path)?, Compression::default());
+        encoder.write_all(&data)?;
+        return encoder.finish().map(|_| ());
+    }
+
+    fs::write(path, data)
+}
+
+/// Languages whose name matches the regular expression `pattern`, ignoring case.
+pub fn search_regex<'a>(
+    languages: &'a [Language],
+    pattern: &str,
+) -> Result<Vec<&'a Language>, regex::Error> {
+    search_regex_with(languages, pattern, &MatchOptions::default())
+}
+
+/// Like [`search_regex`], with explicit [`MatchOptions`].
+pub fn search_regex_with<'a>(
+    languages: &'a [Language],
+    pattern: &str,
+    options: &MatchOptions,
+) -> Result<Vec<&'a Language>, regex::Error> {
+    let regex = RegexBuilder::new(pattern)
+        .case_insensitive(!options.case_sensitive)
+        .build()?;
+    Ok(languages
+        .iter()
+        .filter(|lang| regex.is_match(&lang.name))
+        .collect())
+}
+
+/// Languages whose whole name matches the glob `pattern` (`*` and `?`
+/// wildcards), ignoring case unless `options` says otherwise.
+pub fn search_glob_with<'a>(
+    languages: &'a [Language],
+    pattern: &str,
+    options: &MatchOptions,
+) -> Result<Vec<&'a Language>, globset::Error> {
+    let glob = GlobBuilder::new(pattern)
+        .case_insensitive(!options.case_sensitive)
+        .build()?
+        .compile_matcher();
+    Ok(languages
+        .iter()
+        .filter(|lang| glob.is_match(&lang.name))
+        .collect())
+}
+
+/// Languages whose name is within a small edit distance of `query`, closest first.
+///
+/// The allowed distance grows with the length of the query, so short queries
+/// only tolerate a single typo.
+pub fn fuzzy_search<'a>(languages: &'a [Language], query: &str) -> Vec<(&'a Language, usize)> {
+    let query = query.to_lowercase();
+    let threshold = query.chars().count() / 3 + 1;
+
+    let mut matches: Vec<_> = languages
+        .iter()
+        .map(|lang| (lang, levenshtein(&lang.name.to_lowercase(), &query)))
+        .filter(|(_, distance)| *distance <= threshold)
+        .collect();
+    matches.sort_by_key(|(_, distance)| *distance);
+    matches
+}
+
+/// Up to three languages with names close to `query`, closest first, for
+/// suggesting after a lookup finds nothing.
+pub fn suggestions<'a>(languages: &'a [Language], query: &str) -> Vec<&'a Language> {
+    fuzzy_search(languages, query)
+        .into_iter()
+        .take(3)
+        .map(|(lang, _)| lang)
+        .collect()
+}
+
+/// Number of single-character insertions, deletions and substitutions
+/// needed to turn `a` into `b`.
+pub fn levenshtein(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+
+    for (i, ca) in a.chars().enumerate() {
+        let mut current = vec![i + 1; b.len() + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let cost = if ca == *cb { 0 } else { 1 };
+            current[j + 1] = (previous[j] + cost)
+                .min(previous[j + 1] + 1)
+                .min(current[j] + 1);
+        }
+        previous = current;
+    }
+
+    previous[b.len()]
+}
+
+/// Languages created in `year`.
+pub fn by_year(languages: &[Language], year: u32) -> Vec<&Language> {
+    languages.iter().filter(|lang| lang.year == year).collect()
+}
+
+/// Languages created within `years`, inclusive.
+pub fn by_year_range(languages: &[Language], years: RangeInclusive<u32>) -> Vec<&Language> {
+    languages
+        .iter()
+        .filter(|lang| years.contains(&lang.year))
+        .collect()
+}
+
+/// Languages with a creator whose name contains `name`, ignoring case.
+pub fn by_creator<'a>(languages: &'a [Language], name: &str) -> Vec<&'a Language> {
+    by_creator_with(languages, name, &MatchOptions::default())
+}
+
+/// Like [`by_creator`], with explicit [`MatchOptions`].
+pub fn by_creator_with<'a>(
+    languages: &'a [Language],
+    name: &str,
+    options: &MatchOptions,
+) -> Vec<&'a Language> {
+    languages
+        .iter()
+        .filter(|lang| lang.creators.iter().any(|c| options.matches(c, name)))
+        .collect()
+}
+
+/// Like [`by_creator_with`], but only languages that have a matching creator
+/// for every one of `names`.
+pub fn by_all_creators_with<'a>(
+    languages: &'a [Language],
+    names: &[String],
+    options: &MatchOptions,
+) -> Vec<&'a Language> {
+    languages
+        .iter()
+        .filter(|lang| {
+            names
+                .iter()
+                .all(|name| lang.creators.iter().any(|c| options.matches(c, name)))
+        })
+        .collect()
+}
+
+/// Languages with a paradigm containing `name`, ignoring case.
+pub fn by_paradigm<'a>(languages: &'a [Language], name: &str) -> Vec<&'a Language> {
+    by_paradigm_with(languages, name, &MatchOptions::default())
+}
+
+/// Like [`by_paradigm`], with explicit [`MatchOptions`].
+pub fn by_paradigm_with<'a>(
+    languages: &'a [Language],
+    name: &str,
+    options: &MatchOptions,
+) -> Vec<&'a Language> {
+    languages
+        .iter()
+        .filter(|lang| lang.paradigm.iter().any(|p| options.matches(p, name)))
+        .collect()
+}
+
+/// Like [`by_paradigm_with`], but languages with a matching paradigm for any
+/// one of `names`.
+pub fn by_any_paradigm_with<'a>(
+    languages: &'a [Language],
+    names: &[String],
+    options: &MatchOptions,
+) -> Vec<&'a Language> {
+    languages
+        .iter()
+        .filter(|lang| {
+            names
+                .iter()
+                .any(|name| lang.paradigm.iter().any(|p| options.matches(p, name)))
+        })
+        .collect()
+}
+
+/// Like [`by_paradigm_with`], but only languages that have a matching
+/// paradigm for every one of `names`.
+pub fn by_all_paradigms_with<'a>(
+    languages: &'a [Language],
+    names: &[String],
+    options: &MatchOptions,
+) -> Vec<&'a Language> {
+    languages
+        .iter()
+        .filter(|lang| {
+            names
+                .iter()
+                .all(|name| lang.paradigm.iter().any(|p| options.matches(p, name)))
+        })
+        .collect()
+}
+
+/// Languages whose typing discipline contains `kind`, ignoring case.
+pub fn by_typing<'a>(languages: &'a [Language], kind: &str) -> Vec<&'a Language> {
+    let kind = kind.to_lowercase();
+    languages
+        .iter()
+        .filter(|lang| lang.typing.to_lowercase().contains(&kind))
+        .collect()
+}
+
+/// Languages tagged with `name`, ignoring case.
+pub fn by_tag<'a>(languages: &'a [Language], name: &str) -> Vec<&'a Language> {
+    let name = name.to_lowercase();
+    languages
+        .iter()
+        .filter(|lang| lang.tags.iter().any(|tag| tag.to_lowercase() == name))
+        .collect()
+}
+
+/// Languages that list `name` in their `influenced_by`, ignoring case.
+pub fn influenced_by<'a>(languages: &'a [Language], name: &str) -> Vec<&'a Language> {
+    let name = name.to_lowercase();
+    languages
+        .iter()
+        .filter(|lang| lang.influenced_by.iter().any(|i| i.to_lowercase() == name))
+        .collect()
+}
+
+/// Up to `count` distinct languages picked uniformly at random.
+///
+/// The same `seed` always yields the same selection for a given dataset.
+pub fn random_sample(languages: &[Language], count: usize, seed: Option<u64>) -> Vec<&Language> {
+    let mut rng = match seed {
+        Some(seed) => StdRng::seed_from_u64(seed),
+        None => rand::make_rng(),
+    };
+    languages.sample(&mut rng, count).collect()
+}
+
+/// The oldest language, if any, picking the first name alphabetically when
+/// several share the year.
+pub fn earliest<'a>(languages: impl IntoIterator<Item = &'a Language>) -> Option<&'a Language> {
+    languages
+        .into_iter()
+        .min_by(|a, b| a.year.cmp(&b.year).then_with(|| a.name.cmp(&b.name)))
+}
+
+/// The most recent language, if any, picking the first name alphabetically
+/// when several share the year.
+pub fn latest<'a>(languages: impl IntoIterator<Item = &'a Language>) -> Option<&'a Language> {
+    languages
+        .into_iter()
+        .min_by(|a, b| b.year.cmp(&a.year).then_with(|| a.name.cmp(&b.name)))
+}
+
+/// Number of languages per paradigm, most common first and ties broken
+/// alphabetically.
+///
+/// Paradigms are grouped case-insensitively and reported in lowercase.
+pub fn paradigm_counts<'a>(
+    languages: impl IntoIterator<Item = &'a Language>,
+) -> Vec<(String, usize)> {
+    let mut counts = BTreeMap::new();
+    for lang in languages {
+        for paradigm in &lang.paradigm {
+            *counts.entry(paradigm.to_lowercase()).or_insert(0) += 1;
+        }
+    }
+
+    let mut counts: Vec<_> = counts.into_iter().collect();
+    counts.sort_by(|(a, a_count), (b, b_count)| b_count.cmp(a_count).then_with(|| a.cmp(b)));
+    counts
+}
+
+/// Every creator with the number of languages they are credited with, sorted
+/// alphabetically.
+///
+/// Names are deduplicated case-insensitively, keeping the first spelling seen.
+pub fn creator_counts<'a>(
+    languages: impl IntoIterator<Item = &'a Language>,
+) -> Vec<(String, usize)> {
+    let mut counts: BTreeMap<String, (String, usize)> = BTreeMap::new();
+    for lang in languages {
+        for creator in &lang.creators {
+            counts
+                .entry(creator.to_lowercase())
+                .or_insert_with(|| (creator.clone(), 0))
+                .1 += 1;
+        }
+    }
+
+    // Keyed by the lowercased name, so the values are already in order
+    counts.into_values().collect()
+}
+
+/// Number of languages per typing discipline, most common first.
+///
+/// Disciplines are grouped case-insensitively and reported in lowercase.
+pub fn typing_counts<'a>(
+    languages: impl IntoIterator<Item = &'a Language>,
+) -> Vec<(String, usize)> {
+    let mut counts = BTreeMap::new();
+    for lang in languages {
+        *counts.entry(lang.typing.to_lowercase()).or_insert(0) += 1;
+    }
+
+    let mut counts: Vec<_> = counts.into_iter().collect();
+    counts.sort_by(|(a, a_count), (b, b_count)| b_count.cmp(a_count).then_with(|| a.cmp(b)));
+    counts
+}
+
+/// Number of languages per tag, most common first and ties broken
+/// alphabetically.
+///
+/// Tags are grouped case-insensitively and reported in lowercase.
+pub fn tag_counts<'a>(languages: impl IntoIterator<Item = &'a Language>) -> Vec<(String, usize)> {
+    let mut counts = BTreeMap::new();
+    for lang in languages {
+        for tag in &lang.tags {
+            *counts.entry(tag.to_lowercase()).or_insert(0) += 1;
+        }
+    }
+
+    let mut counts: Vec<_> = counts.into_iter().collect();
+    counts.sort_by(|(a, a_count), (b, b_count)| b_count.cmp(a_count).then_with(|| a.cmp(b)));
+    counts
+}
+
+/// Languages listed in other languages' `influenced_by`, with how many
+/// languages they influenced, most influential first.
+///
+/// Names are resolved case-insensitively; languages that influenced nothing
+/// in the dataset are left out.
+pub fn influence_counts<'a>(
+    languages: impl IntoIterator<Item = &'a Language>,
+) -> Vec<(&'a Language, usize)> {
+    let mut counts: Vec<_> = influence_totals(languages)
+        .into_iter()
+        .filter(|(_, count)| *count > 0)
+        .collect();
+    counts.sort_by(|(a, a_count), (b, b_count)| {
+        b_count.cmp(a_count).then_with(|| a.name.cmp(&b.name))
+    });
+    counts
+}
+
+/// Every language with how many languages it influenced, most influential
+/// first. Ties go to the older language, then alphabetically.
+pub fn influence_ranking<'a>(
+    languages: impl IntoIterator<Item = &'a Language>,
+) -> Vec<(&'a Language, usize)> {
+    let mut ranking = influence_totals(languages);
+    ranking.sort_by(|(a, a_count), (b, b_count)| {
+        b_count
+            .cmp(a_count)
+            .then_with(|| a.year.cmp(&b.year))
+            .then_with(|| a.name.cmp(&b.name))
+    });
+    ranking
+}
+
+// Influence counts in dataset order, including languages that influenced nothing
+fn influence_totals<'a>(
+    languages: impl IntoIterator<Item = &'a Language>,
+) -> Vec<(&'a Language, usize)> {
+    let languages: Vec<_> = languages.into_iter().collect();
+    let index: HashMap<_, _> = languages
+        .iter()
+        .enumerate()
+        .map(|(i, lang)| (lang.name.to_lowercase(), i))
+        .collect();
+
+    let mut counts = vec![0; languages.len()];
+    for (own, lang) in languages.iter().enumerate() {
+        for influence in &lang.influenced_by {
+            if let Some(&i) = index.get(&influence.to_lowercase())
+                && i != own
+            {
+                counts[i] += 1;
+            }
+        }
+    }
+
+    languages.into_iter().zip(counts).collect()
+}
+
+/// Languages grouped by creation year, earliest year first.
+pub fn group_by_year<'a>(
+    languages: impl IntoIterator<Item = &'a Language>,
+) -> BTreeMap<u32, Vec<&'a Language>> {
+    let mut years: BTreeMap<u32, Vec<&Language>> = BTreeMap::new();
+    for lang in languages {
+        years.entry(lang.year).or_default().push(lang);
+    }
+    years
+}
+
+/// Languages grouped by lowercased typing discipline, sorted alphabetically.
+pub fn group_by_typing<'a>(
+    languages: impl IntoIterator<Item = &'a Language>,
+) -> BTreeMap<String, Vec<&'a Language>> {
+    let mut groups: BTreeMap<String, Vec<&Language>> = BTreeMap::new();
+    for lang in languages {
+        groups
+            .entry(lang.typing.to_lowercase())
+            .or_default()
+            .push(lang);
+    }
+    groups
+}
+
+/// Languages grouped by lowercased paradigm, sorted alphabetically. A language
+/// appears in the group of every paradigm it lists.
+pub fn group_by_paradigm<'a>(
+    languages: impl IntoIterator<Item = &'a Language>,
+) -> BTreeMap<String, Vec<&'a Language>> {
+    let mut groups: BTreeMap<String, Vec<&Language>> = BTreeMap::new();
+    for lang in languages {
+        for paradigm in &lang.paradigm {
+            let group = groups.entry(paradigm.to_lowercase()).or_default();
+            // A paradigm listed twice in different cases still counts once
+            if !group.last().is_some_and(|last| std::ptr::eq(*last, lang)) {
+                group.push(lang);
+            }
+        }
+    }
+    groups
+}
+
+/// Languages grouped by creation year, busiest years first and ties broken by
+/// the earlier year.
+pub fn busiest_years<'a>(
+    languages: impl IntoIterator<Item = &'a Language>,
+) -> Vec<(u32, Vec<&'a Language>)> {
+    let mut years: Vec<_> = group_by_year(languages).into_iter().collect();
+    // Stable sort keeps the BTreeMap's ascending years within equal counts
+    years.sort_by_key(|(_, langs)| Reverse(langs.len()));
+    years
+}
+
+/// Number of languages per decade, keyed by the decade's first year.
+pub fn decade_counts<'a>(
+    languages: impl IntoIterator<Item = &'a Language>,
+) -> BTreeMap<u32, usize> {
+    let mut counts = BTreeMap::new();
+    for lang in languages {
+        *counts.entry(lang.year / 10 * 10).or_insert(0) += 1;
+    }
+    counts
+}
+
+/// Number of languages by how many paradigms they list, keyed by the number
+/// of paradigms.
+pub fn paradigms_per_language<'a>(
+    languages: impl IntoIterator<Item = &'a Language>,
+) -> BTreeMap<usize, usize> {
+    let mut counts = BTreeMap::new();
+    for lang in languages {
+        *counts.entry(lang.paradigm.len()).or_insert(0) += 1;
+    }
+    counts
+}
+
+/// How creation years are spread across a dataset.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct YearDistribution {
+    pub p25: f64,
+    pub median: f64,
+    pub p75: f64,
+    /// Population standard deviation of the years.
+    pub std_dev: f64,
+}
+
+/// Percentiles and spread of the creation years, or `None` for no languages.
+///
+/// Percentiles interpolate linearly between the two nearest sorted years.
+pub fn year_distribution<'a>(
+    languages: impl IntoIterator<Item = &'a Language>,
+) -> Option<YearDistribution> {
+    let mut years: Vec<f64> = languages.into_iter().map(|lang| lang.year as f64).collect();
+    if years.is_empty() {
+        return None;
+    }
+    years.sort_by(f64::total_cmp);
+
+    let mean = years.iter().sum::<f64>() / years.len() as f64;
+    let variance = years.iter().map(|year| (year - mean).powi(2)).sum::<f64>() / years.len() as f64;
+
+    Some(YearDistribution {
+        p25: percentile(&years, 0.25),
+        median: percentile(&years, 0.5),
+        p75: percentile(&years, 0.75),
+        std_dev: variance.sqrt(),
+    })
+}
+
+fn percentile(sorted: &[f64], fraction: f64) -> f64 {
+    let rank = fraction * (sorted.len() - 1) as f64;
+    let (low, high) = (rank.floor() as usize, rank.ceil() as usize);
+    sorted[low] + (sorted[high] - sorted[low]) * (rank - low as f64)
+}
+
+/// The current calendar year (UTC) according to the system clock.
+pub fn current_year() -> u32 {
+    let secs = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_secs());
+    let days = (secs / 86_400) as i64;
+
+    // Civil-from-days conversion, see http://howardhinnant.github.io/date_algorithms.html
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z - era * 146_097;
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let year = yoe + era * 400 + if mp >= 10 { 1 } else { 0 };
+
+    year as u32
+}
@@ -1,189 +1,2001 @@
-use clap::{Parser, Subcommand};
-use prettytable::{Table, row};
-use serde::{Deserialize, Serialize};
-use std::{env, fs, path::PathBuf};
-
-#[derive(Debug, Serialize, Deserialize, Clone)]
-struct Language {
-    name: String,
-    year: u32,
-    creators: Vec<String>,
-    paradigm: Vec<String>,
-    typing: String,
-    influenced_by: Vec<String>,
-}
+use clap::{Args, CommandFactory, Parser, Subcommand, ValueEnum};
+use clap_complete::Shell;
+use kapa::{Language, LookupError};
+use output::{Column, Output, OutputFormat, noteln, outln};
+use prettytable::{Cell, Row, Table};
+use serde::Deserialize;
+use std::{
+    cmp::Reverse,
+    collections::{HashMap, HashSet},
+    fs,
+    io::{self, IsTerminal},
+    num::NonZeroUsize,
+    ops::RangeInclusive,
+    path::{Path, PathBuf},
+    process, slice,
+};
+
+mod config;
+mod output;
+mod progress;
+mod tui;
+mod watch;
+
+// Exit statuses, as listed in `--help`
+const EXIT_NO_MATCHES: i32 = 1;
+const EXIT_FAILURE: i32 = 1;
+const EXIT_USAGE: i32 = 2;
+const EXIT_DATA: i32 = 3;
 
 #[derive(Debug, Parser)]
 #[clap(
     name = "kapa",
     version = "1.0",
-    about = "Programming language information tool"
+    about = "Programming language information tool",
+    after_help = "Exit status:
+  0  success, with at least one result
+  1  nothing matched, or the command failed
+  2  invalid arguments or usage
+  3  the data or config file could not be loaded"
 )]
 struct Cli {
     #[clap(subcommand)]
     command: Commands,
+
+    /// Output format for language listings [default: table]
+    #[clap(long, value_enum, global = true)]
+    format: Option<OutputFormat>,
+
+    /// Table columns to show, in order (e.g. name,year,typing)
+    #[clap(long, value_enum, value_delimiter = ',', global = true)]
+    columns: Vec<Column>,
+
+    /// Truncate table cells longer than this many characters
+    #[clap(long, value_name = "N", global = true)]
+    max_width: Option<NonZeroUsize>,
+
+    /// Disable colored output, even when writing to a terminal
+    #[clap(long, global = true)]
+    no_color: bool,
+
+    /// Parse data files as this format instead of going by their extension
+    /// (.yaml/.yml is YAML, .ndjson/.jsonl is one language per line, anything else JSON)
+    #[clap(long, value_enum, value_name = "FORMAT", global = true)]
+    input_format: Option<InputFormat>,
+
+    /// Reject data files with keys that aren't language fields, such as a misspelled "paradigms"
+    #[clap(long, global = true)]
+    strict_json: bool,
+
+    /// Always parse the data file instead of reusing the cached copy
+    #[clap(long, global = true)]
+    no_cache: bool,
+
+    /// Write results to this file instead of stdout
+    #[clap(long, short, value_name = "PATH", global = true)]
+    output: Option<PathBuf>,
+
+    /// Emphasize this text wherever it appears in table cells (needs color)
+    #[clap(long, value_name = "TERM", global = true)]
+    highlight: Option<String>,
+
+    /// In JSON output, add each language's age, influenced_count and paradigm_count
+    #[clap(long, global = true)]
+    enrich: bool,
+
+    /// Leave out headings, empty-result messages and other descriptive lines
+    #[clap(long, short, global = true)]
+    quiet: bool,
+
+    /// Log how the data file is found and loaded to stderr
+    #[clap(long, short, global = true)]
+    verbose: bool,
+
+    /// Load languages from this JSON, YAML or NDJSON file instead of searching the default locations ("-" reads stdin).
+    /// Repeat to merge several files; later files override languages with the same name
+    #[clap(long, value_name = "PATH", global = true)]
+    data_file: Vec<PathBuf>,
+
+    /// Fetch the JSON dataset from this URL, falling back to the last copy
+    /// fetched when the download fails
+    #[clap(long, value_name = "URL", global = true, conflicts_with = "data_file")]
+    data_url: Option<String>,
+}
+
+#[derive(Debug, Args)]
+struct ListingArgs {
+    /// Sort results by the given field
+    #[clap(long, value_enum)]
+    sort: Option<SortKey>,
+
+    /// Reverse the order of results
+    #[clap(long)]
+    reverse: bool,
+
+    /// Show at most this many results
+    #[clap(long)]
+    limit: Option<NonZeroUsize>,
+
+    /// Re-run whenever the data file changes, until interrupted with Ctrl-C
+    #[clap(long)]
+    watch: bool,
+
+    /// Print one line per language instead of a table
+    #[clap(long)]
+    summary: bool,
+
+    /// Add a description column to the table
+    #[clap(long)]
+    with_description: bool,
+}
+
+#[derive(Debug, Args)]
+struct FilterArgs {
+    /// Only languages whose name contains this text
+    #[clap(long)]
+    name: Option<String>,
+
+    /// Only languages created in this year or inclusive range (e.g. 1990..2000)
+    #[clap(long, visible_alias = "year-range", value_parser = parse_year_range)]
+    year: Option<RangeInclusive<u32>>,
+
+    /// Only languages with a creator whose name contains this text (repeat to
+    /// require several creators)
+    #[clap(long = "creator", value_name = "CREATOR")]
+    creators: Vec<String>,
+
+    /// Only languages with a paradigm containing this text
+    #[clap(long)]
+    paradigm: Option<String>,
+
+    /// Only languages whose typing discipline contains this text
+    #[clap(long)]
+    typing: Option<String>,
+}
+
+impl FilterArgs {
+    fn to_filter(&self) -> kapa::Filter {
+        kapa::Filter {
+            name: self.name.clone(),
+            years: self.year.clone(),
+            creators: self.creators.clone(),
+            paradigm: self.paradigm.clone(),
+            typing: self.typing.clone(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum ExportFormat {
+    Csv,
+    Html,
+    Json,
+    Yaml,
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum GroupKey {
+    Year,
+    Typing,
+    Paradigm,
+}
+
+/// Order of the rows in a table of counts.
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum CountOrder {
+    /// Most common first
+    Count,
+    /// Alphabetically
+    Name,
+}
+
+/// Syntax of the data files, overriding their extensions.
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum InputFormat {
+    /// A JSON array of languages
+    Json,
+    /// A YAML sequence of languages
+    Yaml,
+    /// One JSON language object per line
+    Ndjson,
+}
+
+impl From<InputFormat> for kapa::DataFormat {
+    fn from(format: InputFormat) -> Self {
+        match format {
+            InputFormat::Json => kapa::DataFormat::Json,
+            InputFormat::Yaml => kapa::DataFormat::Yaml,
+            InputFormat::Ndjson => kapa::DataFormat::Ndjson,
+        }
+    }
+}
+
+/// Which definition `merge` keeps when files disagree about a language.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum Prefer {
+    /// The one from the earliest file
+    First,
+    /// The one from the latest file
+    Last,
+}
+
+/// How several values given to one filter combine.
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum MatchMode {
+    /// At least one value must match
+    Any,
+    /// Every value must match
+    All,
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum SortKey {
+    Name,
+    Year,
+    Creators,
+}
+
+impl ListingArgs {
+    fn sort(&self, languages: &mut [&Language]) {
+        match self.sort {
+            Some(SortKey::Name) => languages.sort_by_key(|lang| lang.name.to_lowercase()),
+            Some(SortKey::Year) => languages.sort_by_key(|lang| lang.year),
+            Some(SortKey::Creators) => {
+                languages.sort_by_key(|lang| lang.creators.join(", ").to_lowercase())
+            }
+            None => {}
+        }
+
+        if self.reverse {
+            languages.reverse();
+        }
+    }
 }
 
 #[derive(Debug, Subcommand)]
 enum Commands {
     /// List all languages
-    List,
+    List {
+        #[clap(flatten)]
+        listing: ListingArgs,
+
+        /// Page of results to show, starting at 1
+        #[clap(long, requires = "page_size")]
+        page: Option<NonZeroUsize>,
+
+        /// Number of languages per page
+        #[clap(long, conflicts_with = "limit")]
+        page_size: Option<NonZeroUsize>,
+
+        /// Only languages created in or after this year
+        #[clap(long)]
+        min_year: Option<u32>,
+
+        /// Only languages created in or before this year
+        #[clap(long)]
+        max_year: Option<u32>,
+
+        /// Only languages created after this year, not including it
+        #[clap(long, value_name = "YEAR")]
+        after: Option<u32>,
+
+        /// Only languages created before this year, not including it
+        #[clap(long, value_name = "YEAR")]
+        before: Option<u32>,
+
+        /// Add a column with how many years old each language is
+        #[clap(long)]
+        age: bool,
+
+        /// Add a column with how many languages in the dataset each one influenced
+        #[clap(long)]
+        influenced_count: bool,
+
+        /// Print a separate table for each year, typing discipline or paradigm
+        #[clap(long, value_enum, value_name = "FIELD", conflicts_with_all = ["page_size", "limit", "summary"])]
+        group_by: Option<GroupKey>,
+    },
 
     /// Search for a specific language
     Search {
         #[clap(help = "Language name to search for")]
         name: String,
+
+        /// Match names by edit distance so typos still find results
+        #[clap(long, conflicts_with_all = ["sort", "reverse", "watch"])]
+        fuzzy: bool,
+
+        /// Treat the name as a regular expression
+        #[clap(long, conflicts_with = "fuzzy")]
+        regex: bool,
+
+        /// Treat the name as a glob pattern matched against the whole name (e.g. "*Script")
+        #[clap(long, conflicts_with_all = ["fuzzy", "regex", "exact"])]
+        glob: bool,
+
+        /// Match letter case exactly
+        #[clap(long, conflicts_with = "fuzzy")]
+        case_sensitive: bool,
+
+        /// Only match names equal to the query rather than containing it
+        #[clap(long, conflicts_with_all = ["fuzzy", "regex"])]
+        exact: bool,
+
+        /// Add a column with how many years old each language is
+        #[clap(long)]
+        age: bool,
+
+        #[clap(flatten)]
+        listing: ListingArgs,
     },
 
-    /// Display languages created in a specific year
+    /// Show every field of a single language
+    Info {
+        #[clap(help = "Exact name of the language")]
+        name: String,
+
+        /// Print only the value of this field, with lists separated by commas
+        #[clap(long, value_enum, value_name = "FIELD")]
+        field: Option<Column>,
+
+        /// Also list the languages that name this one in their influenced_by
+        /// (table output only)
+        #[clap(long, conflicts_with = "field")]
+        include_influenced: bool,
+    },
+
+    /// Display languages created in a specific year or range of years
     Year {
-        #[clap(help = "Year to filter languages by")]
-        year: u32,
+        #[clap(
+            value_parser = parse_year_range,
+            help = "Year to filter languages by, or an inclusive range such as 1990..2000"
+        )]
+        year: RangeInclusive<u32>,
+
+        /// Show the matches as a chronological timeline instead of a table
+        #[clap(long, conflicts_with_all = ["sort", "reverse", "summary"])]
+        timeline: bool,
+
+        #[clap(flatten)]
+        listing: ListingArgs,
     },
 
     /// Display languages by creator
     Creator {
-        #[clap(help = "Creator name to filter by")]
+        #[clap(
+            required = true,
+            help = "Creator name to filter by; with several names, languages must have them all"
+        )]
+        names: Vec<String>,
+
+        /// Match letter case exactly
+        #[clap(long)]
+        case_sensitive: bool,
+
+        /// Only match creators whose full name equals the given name
+        #[clap(long)]
+        strict: bool,
+
+        #[clap(flatten)]
+        listing: ListingArgs,
+    },
+
+    /// Display languages by paradigm
+    Paradigm {
+        #[clap(required = true, help = "Paradigm to filter by; several can be given")]
+        names: Vec<String>,
+
+        /// Whether languages need any or all of the given paradigms
+        #[clap(long = "match", value_enum, value_name = "MODE", default_value = "any")]
+        match_mode: MatchMode,
+
+        /// Match letter case exactly
+        #[clap(long)]
+        case_sensitive: bool,
+
+        #[clap(flatten)]
+        listing: ListingArgs,
+    },
+
+    /// Display languages by type system
+    Typing {
+        #[clap(help = "Typing discipline to filter by")]
+        kind: String,
+
+        #[clap(flatten)]
+        listing: ListingArgs,
+    },
+
+    /// Display languages with a tag
+    Tag {
+        #[clap(help = "Tag to filter by")]
+        name: String,
+
+        #[clap(flatten)]
+        listing: ListingArgs,
+    },
+
+    /// Display languages influenced by another language
+    InfluencedBy {
+        #[clap(help = "Name of the influencing language")]
+        name: String,
+
+        #[clap(flatten)]
+        listing: ListingArgs,
+    },
+
+    /// Show the languages a language was influenced by, recursively
+    Deps {
+        #[clap(help = "Exact name of the language")]
+        name: String,
+    },
+
+    /// Show the languages influenced by a language, recursively
+    Descendants {
+        #[clap(help = "Exact name of the language")]
+        name: String,
+    },
+
+    /// Combine several data files into one, reporting languages they define differently
+    Merge {
+        #[clap(required = true, num_args = 2.., help = "Data files to merge, in order")]
+        inputs: Vec<PathBuf>,
+
+        /// File to write the merged dataset to
+        #[clap(long, value_name = "PATH")]
+        into: PathBuf,
+
+        /// Which definition to keep when files disagree about a language
+        #[clap(long, value_enum, default_value = "last")]
+        prefer: Prefer,
+    },
+
+    /// Show languages added, removed or changed between two data files
+    Diff {
+        #[clap(help = "Original data file")]
+        old: PathBuf,
+
+        #[clap(help = "Updated data file")]
+        new: PathBuf,
+    },
+
+    /// Display randomly picked languages
+    Random {
+        /// Number of distinct languages to pick
+        #[clap(long, default_value = "1")]
+        count: NonZeroUsize,
+
+        /// Seed for a reproducible selection
+        #[clap(long)]
+        seed: Option<u64>,
+    },
+
+    /// Compare two languages side by side
+    Compare {
+        #[clap(help = "First language to compare")]
+        first: String,
+
+        #[clap(help = "Second language to compare")]
+        second: String,
+    },
+
+    /// Check the dataset for structural problems
+    Validate,
+
+    /// Generate a shell completion script
+    Completions {
+        #[clap(value_enum, help = "Shell to generate completions for")]
+        shell: Shell,
+    },
+
+    /// Write a starter languages.json to the user data directory
+    Init {
+        /// Write an empty dataset instead of the built-in languages
+        #[clap(long)]
+        empty: bool,
+
+        /// Overwrite the file if it already exists
+        #[clap(long)]
+        force: bool,
+    },
+
+    /// Show which data file would be loaded and every location searched for one
+    Which,
+
+    /// Print every language name, one per line, in alphabetical order
+    Names,
+
+    /// List every creator and how many languages they are credited with
+    Creators {
+        /// Only creators credited with at least this many languages, most prolific first
+        #[clap(long, value_name = "N")]
+        min_languages: Option<usize>,
+    },
+
+    /// List every paradigm and how many languages use it
+    Paradigms,
+
+    /// List every year with languages and how many were created in it
+    Years,
+
+    /// Display languages matching every given filter
+    Filter {
+        #[clap(flatten)]
+        filter: FilterArgs,
+
+        #[clap(flatten)]
+        listing: ListingArgs,
+    },
+
+    /// Display the years in which the most languages were created
+    Top {
+        #[clap(default_value_t = 10, help = "Number of years to show")]
+        n: usize,
+    },
+
+    /// Rank every language by how many languages in the dataset it influenced
+    Ranking {
+        /// Show at most this many languages
+        #[clap(long)]
+        limit: Option<NonZeroUsize>,
+    },
+
+    /// Print only the number of languages matching the given filters
+    Count {
+        #[clap(flatten)]
+        filter: FilterArgs,
+    },
+
+    /// Export the entire dataset to another format
+    Export {
+        #[clap(value_enum, value_name = "FORMAT", help = "Format to export to")]
+        target: ExportFormat,
+
+        #[clap(help = "File to write to (defaults to stdout)")]
+        path: Option<PathBuf>,
+
+        /// Wrap HTML exports in a complete, minimally styled page
+        #[clap(long)]
+        standalone: bool,
+    },
+
+    /// Print the influence graph in Graphviz DOT format
+    Graph {
+        /// Print a JSON object mapping each language to the languages it was influenced by instead
+        #[clap(long)]
+        json: bool,
+    },
+
+    /// Browse the dataset interactively
+    Tui,
+
+    /// Add a language to the dataset file
+    Add {
+        /// Name of the language
+        #[clap(long)]
+        name: String,
+
+        /// Year the language was created
+        #[clap(long)]
+        year: u32,
+
+        /// Creator of the language (repeatable)
+        #[clap(long = "creator", value_name = "CREATOR")]
+        creators: Vec<String>,
+
+        /// Paradigm of the language (repeatable)
+        #[clap(long)]
+        paradigm: Vec<String>,
+
+        /// Typing discipline of the language
+        #[clap(long)]
+        typing: String,
+
+        /// Language that influenced this one (repeatable)
+        #[clap(long)]
+        influenced_by: Vec<String>,
+
+        /// Alternate name the language is known by (repeatable)
+        #[clap(long = "alias", value_name = "ALIAS")]
+        aliases: Vec<String>,
+
+        /// A sentence or two about the language
+        #[clap(long)]
+        description: Option<String>,
+
+        /// Category for the language, such as "systems" (repeatable)
+        #[clap(long = "tag", value_name = "TAG")]
+        tags: Vec<String>,
+    },
+
+    /// Remove a language from the dataset file
+    Remove {
+        #[clap(help = "Exact name of the language to remove")]
         name: String,
+
+        /// Report what would be removed without changing the file
+        #[clap(long)]
+        dry_run: bool,
     },
 
     /// Display statistics
-    Stats,
-}
-
-fn load_languages() -> Vec<Language> {
-    // Try multiple possible locations for the data file
-    let paths = [
-        // Development location
-        PathBuf::from("languages.json"),
-        // Next to executable
-        env::current_exe()
-            .unwrap()
-            .parent()
-            .unwrap()
-            .join("languages.json"),
-        // System data directory
-        PathBuf::from("/usr/local/share/kapa/languages.json"),
-        // User data directory
-        dirs::data_local_dir().unwrap().join("kapa/languages.json"),
-    ];
-
-    let data = paths
+    Stats {
+        /// Also show how many languages were created in each decade
+        #[clap(long)]
+        by_decade: bool,
+
+        /// Also show the median, quartiles and standard deviation of creation years
+        #[clap(long)]
+        distribution: bool,
+
+        /// Also show how many languages list each number of paradigms
+        #[clap(long)]
+        count_paradigms_per_language: bool,
+
+        /// Order of the paradigm counts table
+        #[clap(long, value_enum, value_name = "ORDER", default_value = "count")]
+        sort_paradigms: CountOrder,
+
+        /// Only include languages with a paradigm containing this text
+        #[clap(long)]
+        paradigm: Option<String>,
+
+        /// Only include languages with a creator whose name contains this text
+        #[clap(long, conflicts_with = "paradigm")]
+        creator: Option<String>,
+    },
+}
+
+impl Commands {
+    fn listing_mut(&mut self) -> Option<&mut ListingArgs> {
+        match self {
+            Commands::List { listing, .. }
+            | Commands::Search {
+                listing,
+                fuzzy: false,
+                ..
+            }
+            | Commands::Year { listing, .. }
+            | Commands::Creator { listing, .. }
+            | Commands::Paradigm { listing, .. }
+            | Commands::Typing { listing, .. }
+            | Commands::Tag { listing, .. }
+            | Commands::InfluencedBy { listing, .. }
+            | Commands::Filter { listing, .. } => Some(listing),
+            _ => None,
+        }
+    }
+}
+
+/// Resolves `name` with `find`, falling back to the dataset's aliases when it fails.
+fn lookup<'a>(
+    languages: &'a [Language],
+    name: &str,
+    find: fn(&'a [Language], &str) -> Result<&'a Language, LookupError>,
+) -> Result<&'a Language, LookupError> {
+    find(languages, name).or_else(|err| resolve_alias(languages, name).ok_or(err))
+}
+
+// Notes the resolution on stderr so it doesn't end up in JSON or CSV output
+fn resolve_alias<'a>(languages: &'a [Language], name: &str) -> Option<&'a Language> {
+    let lang = kapa::resolve_alias(languages, name)?;
+    eprintln!("Note: '{}' is an alias for {}", name, lang.name);
+    Some(lang)
+}
+
+/// Reports a failed lookup, suggesting close names when nothing matched, and exits.
+fn lookup_failed(languages: &[Language], err: LookupError) -> ! {
+    eprintln!("Error: {}", err);
+    if let LookupError::NotFound(query) = &err {
+        suggest(languages, query);
+    }
+    process::exit(EXIT_NO_MATCHES);
+}
+
+// Goes to stderr, like the alias note, so it never mixes with results
+fn suggest(languages: &[Language], query: &str) {
+    let names: Vec<_> = kapa::suggestions(languages, query)
         .iter()
-        .find_map(|path| fs::read_to_string(path).ok())
-        .unwrap_or_else(|| {
-            let searched_paths = paths
-                .iter()
-                .map(|p| p.display().to_string())
-                .collect::<Vec<_>>()
-                .join("\n- ");
-            panic!(
-                "Could not find languages.json in any of these locations:\n- {}\n\n\
-                Please ensure the data file exists in one of these paths",
-                searched_paths
-            )
-        });
+        .map(|lang| lang.name.as_str())
+        .collect();
+    if !names.is_empty() {
+        eprintln!("Did you mean: {}?", names.join(", "));
+    }
+}
+
+fn parse_year_range(value: &str) -> Result<RangeInclusive<u32>, String> {
+    let Some((start, end)) = value.split_once("..") else {
+        let year = value.parse::<u32>().map_err(|err| err.to_string())?;
+        return Ok(year..=year);
+    };
+
+    let start = start.parse::<u32>().map_err(|err| err.to_string())?;
+    let end = end.parse::<u32>().map_err(|err| err.to_string())?;
+    if start > end {
+        return Err(format!("range start {} is after its end {}", start, end));
+    }
+
+    Ok(start..=end)
+}
+
+// `min` and `max` are inclusive, `after` and `before` exclusive
+fn list_years(
+    languages: &[Language],
+    min: Option<u32>,
+    max: Option<u32>,
+    after: Option<u32>,
+    before: Option<u32>,
+) -> Vec<&Language> {
+    if let (Some(min), Some(max)) = (min, max)
+        && min > max
+    {
+        eprintln!("Error: --min-year {} is after --max-year {}", min, max);
+        process::exit(EXIT_USAGE);
+    }
+    if let (Some(after), Some(before)) = (after, before)
+        && after.saturating_add(1) >= before
+    {
+        eprintln!(
+            "Error: no years are both after {} and before {}",
+            after, before
+        );
+        process::exit(EXIT_USAGE);
+    }
+
+    kapa::by_year_range(languages, min.unwrap_or(0)..=max.unwrap_or(u32::MAX))
+        .into_iter()
+        .filter(|lang| {
+            after.is_none_or(|after| lang.year > after)
+                && before.is_none_or(|before| lang.year < before)
+        })
+        .collect()
+}
 
-    serde_json::from_str(&data).expect("Failed to parse JSON data")
+// Numbers, and the headers above them, are right-aligned so their digits line up
+fn numeric_header(label: &str, title: &str) -> Row {
+    Row::new(vec![
+        Cell::new(label).style_spec("bFg"),
+        Cell::new(title).style_spec("bFgr"),
+    ])
 }
 
-fn print_languages_table(languages: &[Language]) {
-    let mut table = Table::new();
+fn numeric_row(label: &str, value: impl ToString) -> Row {
+    Row::new(vec![
+        Cell::new(label),
+        Cell::new(&value.to_string()).style_spec("r"),
+    ])
+}
+
+/// Prints the sorted, limited results, returning whether there were any.
+fn print_listing(
+    mut languages: Vec<&Language>,
+    listing: &ListingArgs,
+    output: &mut Output,
+    heading: &str,
+    empty: &str,
+) -> bool {
+    listing.sort(&mut languages);
+
+    let total = languages.len();
+    if let Some(limit) = listing.limit {
+        languages.truncate(limit.get());
+    }
+
+    output.print_results(&languages, heading, empty);
+    if (output.format == OutputFormat::Table || output.summary) && languages.len() < total {
+        noteln!(output, "(showing {} of {})", languages.len(), total);
+    }
+    total > 0
+}
 
-    table.add_row(row![bFg=> "Name", "Year", "Creators", "Paradigm", "Typing"]);
+/// Prints where the dataset would come from, without loading it.
+fn which(
+    output: &mut Output,
+    data_url: Option<&str>,
+    data_files: &[PathBuf],
+    config_data_file: Option<&Path>,
+) {
+    let candidates = kapa::data_file_candidates();
+    let found = candidates.iter().find(|path| path.is_file());
+
+    if let Some(url) = data_url {
+        outln!(output, "Using: {} (from --data-url)", url);
+    } else if !data_files.is_empty() {
+        for path in data_files {
+            outln!(output, "Using: {} (from --data-file)", path.display());
+        }
+    } else if let Some(path) = config_data_file {
+        outln!(output, "Using: {} (from the config file)", path.display());
+    } else if let Some(path) = found {
+        outln!(output, "Using: {}", path.display());
+    } else {
+        outln!(output, "Using: the built-in dataset");
+    }
+
+    outln!(output, "\nSearched:");
+    for path in &candidates {
+        let status = if path.is_file() { "found" } else { "not found" };
+        outln!(output, "  {} ({})", path.display(), status);
+    }
+}
 
+/// Writes the starter dataset to the user data directory.
+fn init(output: &mut Output, empty: bool, force: bool) {
+    let Some(path) = kapa::user_data_file() else {
+        eprintln!("Error: could not determine the user data directory");
+        process::exit(EXIT_FAILURE);
+    };
+    if path.exists() && !force {
+        eprintln!(
+            "Error: {} already exists, pass --force to overwrite it",
+            path.display()
+        );
+        process::exit(EXIT_FAILURE);
+    }
+
+    let data = if empty { "[]\n" } else { kapa::EMBEDDED_DATA };
+    let written = match path.parent() {
+        Some(dir) => fs::create_dir_all(dir),
+        None => Ok(()),
+    }
+    .and_then(|()| fs::write(&path, data));
+    if let Err(err) = written {
+        eprintln!("Error: could not write {}: {}", path.display(), err);
+        process::exit(EXIT_FAILURE);
+    }
+
+    outln!(output, "Created {}", path.display());
+}
+
+fn writable_data_file(data_files: &[PathBuf]) -> &Path {
+    match data_files {
+        [path] => path,
+        [] => {
+            eprintln!(
+                "Error: cannot modify the built-in dataset, pass --data-file to choose a file \
+                 or run `kapa init` to create one"
+            );
+            process::exit(EXIT_USAGE);
+        }
+        _ => {
+            eprintln!("Error: cannot modify a dataset merged from several files");
+            process::exit(EXIT_USAGE);
+        }
+    }
+}
+
+/// Writes `languages` back to `path`, in `format` when it was loaded with
+/// `--input-format` so the file still loads the same way.
+fn save_dataset(path: &Path, format: Option<kapa::DataFormat>, languages: &[Language]) {
+    if path == Path::new("-") {
+        eprintln!("Error: cannot modify a dataset read from stdin");
+        process::exit(EXIT_USAGE);
+    }
+
+    let format = format.unwrap_or_else(|| kapa::DataFormat::from_path(path));
+    if let Err(err) = kapa::save_languages_as(path, format, languages) {
+        eprintln!("Error: could not write {}: {}", path.display(), err);
+        process::exit(EXIT_FAILURE);
+    }
+}
+
+/// Folds the languages in one input that share a name (ignoring case) into
+/// the definition `prefer` picks, reporting the ones that disagree.
+fn dedup_input(path: &Path, languages: Vec<Language>, prefer: Prefer) -> Vec<Language> {
+    let mut index = HashMap::new();
+    let mut deduped: Vec<Language> = Vec::new();
     for lang in languages {
-        table.add_row(row![
-            lang.name,
-            lang.year,
-            lang.creators.join(", "),
-            lang.paradigm.join(", "),
-            lang.typing
-        ]);
+        let Some(&i) = index.get(&lang.name.to_lowercase()) else {
+            index.insert(lang.name.to_lowercase(), deduped.len());
+            deduped.push(lang);
+            continue;
+        };
+
+        let earlier = slice::from_ref(&deduped[i]);
+        for change in kapa::diff(earlier, slice::from_ref(&lang)).changed {
+            report_conflict(&change, path, "an earlier entry in the same file", prefer);
+        }
+        if prefer == Prefer::Last {
+            deduped[i] = lang;
+        }
     }
+    deduped
+}
 
-    table.printstd();
+fn report_conflict(change: &kapa::Change, path: &Path, earlier: &str, prefer: Prefer) {
+    let fields: Vec<_> = change.fields.iter().map(|field| field.field).collect();
+    let kept = match prefer {
+        Prefer::First => "earlier",
+        Prefer::Last => "later",
+    };
+    eprintln!(
+        "Conflict: {} in {} differs from {} ({}); keeping the {} definition",
+        change.name,
+        path.display(),
+        earlier,
+        fields.join(", "),
+        kept
+    );
+}
+
+/// Loads every input, reports the languages they disagree about and writes
+/// the combined dataset to `into`.
+fn merge(
+    output: &mut Output,
+    inputs: &[PathBuf],
+    into: &Path,
+    prefer: Prefer,
+    options: &kapa::LoadOptions,
+) {
+    let mut languages: Vec<Language> = Vec::new();
+    for path in inputs {
+        let additions = match kapa::load_languages_with(path, options) {
+            Ok(additions) => dedup_input(path, additions, prefer),
+            Err(err) => {
+                eprintln!("Error: {}: {}", path.display(), err);
+                process::exit(EXIT_DATA);
+            }
+        };
+
+        for change in kapa::diff(&languages, &additions).changed {
+            report_conflict(&change, path, "an earlier file", prefer);
+        }
+
+        let additions = match prefer {
+            Prefer::Last => additions,
+            Prefer::First => {
+                let known: HashSet<_> = languages
+                    .iter()
+                    .map(|lang| lang.name.to_lowercase())
+                    .collect();
+                additions
+                    .into_iter()
+                    .filter(|lang| !known.contains(&lang.name.to_lowercase()))
+                    .collect()
+            }
+        };
+        kapa::merge_languages(&mut languages, additions);
+    }
+
+    save_dataset(into, None, &languages);
+    outln!(
+        output,
+        "Merged {} languages from {} files into {}",
+        languages.len(),
+        inputs.len(),
+        into.display()
+    );
+}
+
+fn print_diff(output: &mut Output, diff: &kapa::Diff) {
+    if diff.is_empty() {
+        outln!(output, "No differences");
+        return;
+    }
+
+    for lang in &diff.added {
+        outln!(output, "+ {} ({})", lang.name, lang.year);
+    }
+    for lang in &diff.removed {
+        outln!(output, "- {} ({})", lang.name, lang.year);
+    }
+    for change in &diff.changed {
+        outln!(output, "~ {}", change.name);
+        for field in &change.fields {
+            outln!(
+                output,
+                "    {}: {} -> {}",
+                field.field,
+                field.old,
+                field.new
+            );
+        }
+    }
+
+    outln!(
+        output,
+        "\n{} added, {} removed, {} changed",
+        diff.added.len(),
+        diff.removed.len(),
+        diff.changed.len()
+    );
+}
+
+fn load_url(url: &str) -> Result<Vec<Language>, kapa::LoadError> {
+    match kapa::load_languages_from_url(url) {
+        Err(err @ kapa::LoadError::Fetch { .. }) => match kapa::load_cached_url(url) {
+            Ok(languages) => {
+                eprintln!("Warning: {}, using the last copy fetched", err);
+                Ok(languages)
+            }
+            Err(_) => Err(err),
+        },
+        loaded => loaded,
+    }
+}
+
+fn finish(output: Output, path: Option<&Path>) {
+    let written = output.finish();
+    if let Some(path) = path {
+        println!("Wrote {} bytes to {}", written, path.display());
+    }
 }
 
 fn main() {
-    let cli = Cli::parse();
-    let languages = load_languages();
+    let mut cli = Cli::parse();
+
+    let mut logger =
+        env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("warn"));
+    if cli.verbose {
+        logger.filter_module("kapa", log::LevelFilter::Debug);
+    }
+    logger.format_timestamp(None).format_target(false).init();
+
+    let config = match config::load() {
+        Ok(config) => config,
+        Err(err) => {
+            eprintln!("Error: {}", err);
+            process::exit(EXIT_DATA);
+        }
+    };
+
+    let format = cli.format.or(config.format).unwrap_or(OutputFormat::Table);
+    let color = if cli.no_color {
+        Some(false)
+    } else {
+        config.color
+    };
+    let output_path = cli.output.take();
+    let output = Output::new(format, color, cli.columns, output_path.as_deref());
+    let mut output = match output {
+        Ok(output) => output,
+        Err(err) => {
+            let path = output_path.as_deref().unwrap_or(Path::new("-"));
+            eprintln!("Error: could not create {}: {}", path.display(), err);
+            process::exit(EXIT_FAILURE);
+        }
+    };
+    output.quiet = cli.quiet;
+    output.enrich = cli.enrich;
+    if let Some(term) = &cli.highlight
+        && !term.is_empty()
+    {
+        output.set_highlight(term);
+    }
+    if let Some(listing) = cli.command.listing_mut() {
+        if listing.sort.is_none() {
+            listing.sort = config.sort;
+        }
+        output.summary = listing.summary;
+        if listing.with_description && !output.columns.contains(&Column::Description) {
+            output.columns.push(Column::Description);
+        }
+    }
+
+    if let Commands::Completions { shell } = cli.command {
+        clap_complete::generate(shell, &mut Cli::command(), "kapa", &mut output);
+        finish(output, output_path.as_deref());
+        return;
+    }
+
+    if let Commands::Init { empty, force } = cli.command {
+        init(&mut output, empty, force);
+        finish(output, output_path.as_deref());
+        return;
+    }
+
+    if let Commands::Which = cli.command {
+        which(
+            &mut output,
+            cli.data_url.as_deref(),
+            &cli.data_file,
+            config.data_file.as_deref(),
+        );
+        finish(output, output_path.as_deref());
+        return;
+    }
+
+    let options = kapa::LoadOptions {
+        cache: !cli.no_cache,
+        strict: cli.strict_json,
+        format: cli.input_format.map(Into::into),
+    };
+
+    if let Commands::Diff { old, new } = &cli.command {
+        let loaded = kapa::load_languages_with(old, &options)
+            .and_then(|old| kapa::load_languages_with(new, &options).map(|new| (old, new)));
+        match loaded {
+            Ok((old, new)) => print_diff(&mut output, &kapa::diff(&old, &new)),
+            Err(err) => {
+                eprintln!("Error: {}", err);
+                process::exit(EXIT_DATA);
+            }
+        }
+        finish(output, output_path.as_deref());
+        return;
+    }
 
+    if let Commands::Merge {
+        inputs,
+        into,
+        prefer,
+    } = &cli.command
+    {
+        merge(&mut output, inputs, into, *prefer, &options);
+        finish(output, output_path.as_deref());
+        return;
+    }
+
+    if let Some(url) = &cli.data_url
+        && matches!(cli.command, Commands::Add { .. } | Commands::Remove { .. })
+    {
+        eprintln!("Error: cannot modify a dataset fetched from {}", url);
+        process::exit(EXIT_USAGE);
+    }
+
+    let data_files = if cli.data_url.is_some() {
+        // Nothing local to watch or modify
+        Ok(Vec::new())
+    } else if !cli.data_file.is_empty() {
+        for path in &cli.data_file {
+            log::debug!("using {} from --data-file", path.display());
+        }
+        Ok(cli.data_file)
+    } else if let Some(path) = config.data_file {
+        log::debug!("using {} from the config file", path.display());
+        Ok(vec![path])
+    } else {
+        // No paths means the embedded dataset
+        match kapa::find_data_file() {
+            Ok(path) => Ok(vec![path]),
+            Err(kapa::LoadError::NotFound { .. }) => {
+                log::debug!("no data file found, using the built-in dataset");
+                Ok(Vec::new())
+            }
+            Err(err) => Err(err),
+        }
+    };
+    if cli
+        .command
+        .listing_mut()
+        .is_some_and(|listing| listing.watch)
+    {
+        match &data_files {
+            Ok(paths) => watch::run(paths),
+            Err(err) => {
+                eprintln!("Error: {}", err);
+                process::exit(EXIT_DATA);
+            }
+        }
+    }
+
+    let loaded = data_files.and_then(|paths| {
+        if let Some(url) = &cli.data_url {
+            return load_url(url).map(|languages| (paths, languages));
+        }
+        if paths.is_empty() {
+            return kapa::embedded_languages().map(|languages| (paths, languages));
+        }
+
+        let bar = progress::loading(&paths);
+        let datasets = kapa::load_languages_parallel_with_progress(&paths, &options, || bar.inc(1));
+        bar.finish_and_clear();
+        let datasets = match datasets {
+            Ok(datasets) => datasets,
+            Err(errors) => {
+                for (path, err) in errors {
+                    if paths.len() > 1 {
+                        eprintln!("Error: {}: {}", path.display(), err);
+                    } else {
+                        eprintln!("Error: {}", err);
+                    }
+                }
+                process::exit(EXIT_DATA);
+            }
+        };
+
+        // Merged in command-line order, so later files still win
+        let mut languages = Vec::new();
+        for (path, additions) in paths.iter().zip(datasets) {
+            for name in kapa::merge_languages(&mut languages, additions) {
+                eprintln!(
+                    "Warning: {} from {} overrides an earlier definition",
+                    name,
+                    path.display()
+                );
+            }
+        }
+        Ok((paths, languages))
+    });
+    let (data_files, mut languages) = match loaded {
+        Ok(loaded) => loaded,
+        Err(err) => {
+            eprintln!("Error: {}", err);
+            process::exit(EXIT_DATA);
+        }
+    };
+
+    output.max_width = cli.max_width.map(NonZeroUsize::get);
+    if let Commands::List { age: true, .. } | Commands::Search { age: true, .. } = cli.command
+        && !output.columns.contains(&Column::Age)
+    {
+        output.columns.push(Column::Age);
+    }
+    if let Commands::List {
+        influenced_count: true,
+        ..
+    } = cli.command
+        && !output.columns.contains(&Column::Influenced)
+    {
+        output.columns.push(Column::Influenced);
+    }
+    if output.enrich
+        || output.columns.contains(&Column::Influenced)
+        || matches!(
+            cli.command,
+            Commands::Info {
+                field: Some(Column::Influenced),
+                ..
+            }
+        )
+    {
+        output.influence = kapa::influence_counts(&languages)
+            .into_iter()
+            .map(|(lang, count)| (lang.name.to_lowercase(), count))
+            .collect();
+    }
+
+    // Cleared by the commands that filter the dataset when nothing matches
+    let mut matched = true;
     match cli.command {
-        Commands::List => {
-            println!("Displaying all programming languages:");
-            print_languages_table(&languages);
-        }
-        Commands::Search { name } => {
-            let filtered: Vec<_> = languages
-                .iter()
-                .filter(|lang| lang.name.to_lowercase().contains(&name.to_lowercase()))
-                .cloned()
-                .collect();
-
-            if filtered.is_empty() {
-                println!("No languages found matching '{}'", name);
+        Commands::List {
+            listing,
+            group_by: Some(group_by),
+            min_year,
+            max_year,
+            after,
+            before,
+            ..
+        } => {
+            let languages = list_years(&languages, min_year, max_year, after, before);
+            let mut groups: Vec<(String, Vec<&Language>)> = match group_by {
+                GroupKey::Year => kapa::group_by_year(languages)
+                    .into_iter()
+                    .map(|(year, langs)| (year.to_string(), langs))
+                    .collect(),
+                GroupKey::Typing => kapa::group_by_typing(languages).into_iter().collect(),
+                GroupKey::Paradigm => kapa::group_by_paradigm(languages).into_iter().collect(),
+            };
+            for (_, langs) in &mut groups {
+                listing.sort(langs);
+            }
+
+            matched = !groups.is_empty();
+            if groups.is_empty() && output.format == OutputFormat::Table {
+                noteln!(output, "No languages in dataset");
             } else {
-                println!("Search results for '{}':", name);
-                print_languages_table(&filtered);
+                output.print_groups(&groups);
             }
         }
-        Commands::Year { year } => {
-            let filtered: Vec<_> = languages
-                .iter()
-                .filter(|lang| lang.year == year)
-                .cloned()
-                .collect();
+        Commands::List {
+            listing,
+            page_size: None,
+            min_year,
+            max_year,
+            after,
+            before,
+            ..
+        } => {
+            matched = print_listing(
+                list_years(&languages, min_year, max_year, after, before),
+                &listing,
+                &mut output,
+                "Displaying all programming languages:",
+                "No languages in dataset",
+            );
+        }
+        Commands::List {
+            listing,
+            page,
+            page_size: Some(page_size),
+            min_year,
+            max_year,
+            after,
+            before,
+            ..
+        } => {
+            let page_size = page_size.get();
+            let mut all = list_years(&languages, min_year, max_year, after, before);
+            listing.sort(&mut all);
+            let total = all.len();
+            matched = total > 0;
+            let pages = total.div_ceil(page_size).max(1);
+            let page = page.map_or(1, NonZeroUsize::get);
+            if page > pages {
+                eprintln!("Error: page {} is out of range (1-{})", page, pages);
+                process::exit(EXIT_USAGE);
+            }
 
-            if filtered.is_empty() {
-                println!("No languages created in {}", year);
+            let start = (page - 1) * page_size;
+            let end = (start + page_size).min(total);
+            output.print_results(
+                &all[start..end],
+                "Displaying all programming languages:",
+                "No languages in dataset",
+            );
+            if output.format == OutputFormat::Table {
+                noteln!(
+                    output,
+                    "Page {} of {} ({} languages total)",
+                    page,
+                    pages,
+                    total
+                );
+            }
+        }
+        Commands::Search {
+            name,
+            fuzzy: true,
+            listing,
+            ..
+        } => {
+            let mut matches = kapa::fuzzy_search(&languages, &name);
+            if let Some(limit) = listing.limit {
+                matches.truncate(limit.get());
+            }
+            matched = !matches.is_empty();
+            output.summary = listing.summary;
+
+            if output.format == OutputFormat::Table && !output.summary && !matches.is_empty() {
+                noteln!(output, "Fuzzy matches for '{}':", name);
+                output.print_scored_table(&matches);
+            } else {
+                let filtered: Vec<_> = matches.iter().map(|(lang, _)| *lang).collect();
+                output.print_results(
+                    &filtered,
+                    &format!("Fuzzy matches for '{}':", name),
+                    &format!("No languages found close to '{}'", name),
+                );
+            }
+        }
+        Commands::Search {
+            name,
+            regex,
+            glob,
+            case_sensitive,
+            exact,
+            listing,
+            ..
+        } => {
+            let options = kapa::MatchOptions {
+                case_sensitive,
+                exact,
+            };
+            let filtered = if regex {
+                match kapa::search_regex_with(&languages, &name, &options) {
+                    Ok(filtered) => filtered,
+                    Err(err) => {
+                        eprintln!("Error: invalid regular expression: {}", err);
+                        process::exit(EXIT_USAGE);
+                    }
+                }
+            } else if glob {
+                match kapa::search_glob_with(&languages, &name, &options) {
+                    Ok(filtered) => filtered,
+                    Err(err) => {
+                        eprintln!("Error: invalid glob pattern: {}", err);
+                        process::exit(EXIT_USAGE);
+                    }
+                }
             } else {
-                println!("Languages created in {}:", year);
-                print_languages_table(&filtered);
-            }
-        }
-        Commands::Creator { name } => {
-            let filtered: Vec<_> = languages
-                .iter()
-                .filter(|lang| {
-                    lang.creators
-                        .iter()
-                        .any(|c| c.to_lowercase().contains(&name.to_lowercase()))
-                })
-                .cloned()
-                .collect();
-
-            if filtered.is_empty() {
-                println!("No languages found created by '{}'", name);
+                kapa::search_with(&languages, &name, &options)
+            };
+            // Patterns aren't names, so there is no alias or suggestion to offer
+            let pattern = regex || glob;
+            let filtered = if filtered.is_empty() && !pattern {
+                resolve_alias(&languages, &name).into_iter().collect()
             } else {
-                println!("Languages created by '{}':", name);
-                print_languages_table(&filtered);
+                filtered
+            };
+
+            matched = print_listing(
+                filtered,
+                &listing,
+                &mut output,
+                &format!("Search results for '{}':", name),
+                &format!("No languages found matching '{}'", name),
+            );
+            if !matched && !pattern {
+                suggest(&languages, &name);
+            }
+        }
+        Commands::Info {
+            name,
+            field,
+            include_influenced,
+        } => {
+            if include_influenced && output.format != OutputFormat::Table {
+                eprintln!("Error: --include-influenced only applies to table output");
+                process::exit(EXIT_USAGE);
+            }
+
+            match lookup(&languages, &name, kapa::find_exact) {
+                Ok(lang) if let Some(field) = field => {
+                    outln!(output, "{}", field.text(lang, &output.influence));
+                }
+                Ok(lang) if output.format == OutputFormat::Table => {
+                    let influenced =
+                        include_influenced.then(|| kapa::influenced_by(&languages, &lang.name));
+                    output.print_language_detail(lang, influenced.as_deref());
+                }
+                Ok(lang) => output.print_languages(&[lang]),
+                Err(err) => lookup_failed(&languages, err),
             }
         }
-        Commands::Stats => {
-            let count = languages.len();
-            let earliest = languages.iter().min_by_key(|l| l.year).unwrap();
-            let latest = languages.iter().max_by_key(|l| l.year).unwrap();
+        Commands::Year {
+            year,
+            timeline,
+            listing,
+        } => {
+            let period = if year.start() == year.end() {
+                year.start().to_string()
+            } else {
+                format!("{}-{}", year.start(), year.end())
+            };
 
-            println!("Programming Language Statistics:");
-            println!("- Total languages: {}", count);
-            println!("- Earliest language: {} ({})", earliest.name, earliest.year);
-            println!("- Latest language: {} ({})", latest.name, latest.year);
+            let mut filtered = kapa::by_year_range(&languages, year);
+            if timeline {
+                filtered.sort_by_key(|lang| lang.year);
+                if let Some(limit) = listing.limit {
+                    filtered.truncate(limit.get());
+                }
 
-            let mut paradigm_counts = std::collections::HashMap::new();
-            for lang in &languages {
-                for paradigm in &lang.paradigm {
-                    *paradigm_counts.entry(paradigm).or_insert(0) += 1;
+                matched = !filtered.is_empty();
+                if filtered.is_empty() {
+                    noteln!(output, "No languages created in {}", period);
+                } else {
+                    noteln!(output, "Timeline of languages created in {}:", period);
+                    output.print_timeline(&filtered);
                 }
+            } else {
+                matched = print_listing(
+                    filtered,
+                    &listing,
+                    &mut output,
+                    &format!("Languages created in {}:", period),
+                    &format!("No languages created in {}", period),
+                );
             }
+        }
+        Commands::Creator {
+            names,
+            case_sensitive,
+            strict,
+            listing,
+        } => {
+            let options = kapa::MatchOptions {
+                case_sensitive,
+                exact: strict,
+            };
+            let filtered = kapa::by_all_creators_with(&languages, &names, &options);
+            let quoted: Vec<_> = names.iter().map(|name| format!("'{}'", name)).collect();
+            let creators = quoted.join(" and ");
+            matched = print_listing(
+                filtered,
+                &listing,
+                &mut output,
+                &format!("Languages created by {}:", creators),
+                &format!("No languages found created by {}", creators),
+            );
+        }
+        Commands::Paradigm {
+            names,
+            match_mode,
+            case_sensitive,
+            listing,
+        } => {
+            let options = kapa::MatchOptions {
+                case_sensitive,
+                ..Default::default()
+            };
+            let (filtered, joiner) = match match_mode {
+                MatchMode::Any => (
+                    kapa::by_any_paradigm_with(&languages, &names, &options),
+                    " or ",
+                ),
+                MatchMode::All => (
+                    kapa::by_all_paradigms_with(&languages, &names, &options),
+                    " and ",
+                ),
+            };
+            let quoted: Vec<_> = names.iter().map(|name| format!("'{}'", name)).collect();
+            let paradigms = quoted.join(joiner);
+            matched = print_listing(
+                filtered,
+                &listing,
+                &mut output,
+                &format!("Languages with paradigm {}:", paradigms),
+                &format!("No languages found with paradigm {}", paradigms),
+            );
+        }
+        Commands::Tag { name, listing } => {
+            let filtered = kapa::by_tag(&languages, &name);
+            matched = print_listing(
+                filtered,
+                &listing,
+                &mut output,
+                &format!("Languages tagged '{}':", name),
+                &format!("No languages found tagged '{}'", name),
+            );
+        }
+        Commands::Typing { kind, listing } => {
+            let filtered = kapa::by_typing(&languages, &kind);
+            matched = print_listing(
+                filtered,
+                &listing,
+                &mut output,
+                &format!("Languages with '{}' typing:", kind),
+                &format!("No languages found with typing '{}'", kind),
+            );
+        }
+        Commands::InfluencedBy { name, listing } => {
+            let filtered = kapa::influenced_by(&languages, &name);
+            matched = print_listing(
+                filtered,
+                &listing,
+                &mut output,
+                &format!("Languages influenced by '{}':", name),
+                &format!("No languages found influenced by '{}'", name),
+            );
+        }
+        Commands::Deps { name } => match kapa::find_exact(&languages, &name) {
+            Ok(lang) => output.print_tree(&kapa::ancestry(&languages, lang)),
+            Err(err) => lookup_failed(&languages, err),
+        },
+        Commands::Descendants { name } => match kapa::find_exact(&languages, &name) {
+            Ok(lang) => output.print_tree(&kapa::descendants(&languages, lang)),
+            Err(err) => lookup_failed(&languages, err),
+        },
+        Commands::Random { count, seed } => {
+            let picked = kapa::random_sample(&languages, count.get(), seed);
+            matched = !picked.is_empty();
+
+            if output.format != OutputFormat::Table {
+                output.print_languages(&picked);
+            } else if picked.is_empty() {
+                noteln!(output, "No languages in dataset");
+            } else {
+                for lang in picked {
+                    output.print_language_detail(lang, None);
+                }
+            }
+        }
+        Commands::Compare { first, second } => {
+            let resolved = lookup(&languages, &first, kapa::find).and_then(|left| {
+                lookup(&languages, &second, kapa::find).map(|right| (left, right))
+            });
 
-            println!("\nParadigm Counts:");
+            match resolved {
+                Ok((left, right)) => output.print_comparison(left, right),
+                Err(err) => lookup_failed(&languages, err),
+            }
+        }
+        Commands::Validate => {
+            let problems = kapa::validate(&languages);
+
+            if problems.is_empty() {
+                outln!(output, "No problems found in {} languages", languages.len());
+            } else {
+                for problem in &problems {
+                    outln!(output, "- {}", problem);
+                }
+                outln!(output, "\nFound {} problem(s)", problems.len());
+                finish(output, output_path.as_deref());
+                process::exit(EXIT_FAILURE);
+            }
+        }
+        Commands::Completions { .. }
+        | Commands::Init { .. }
+        | Commands::Which
+        | Commands::Merge { .. }
+        | Commands::Diff { .. } => {
+            unreachable!("handled before loading the dataset")
+        }
+        Commands::Names => {
+            let mut names: Vec<_> = languages.iter().map(|lang| lang.name.as_str()).collect();
+            names.sort_by_key(|name| name.to_lowercase());
+            for name in names {
+                outln!(output, "{}", name);
+            }
+        }
+        Commands::Creators { min_languages } => {
+            let mut counts = kapa::creator_counts(&languages);
+            if let Some(min) = min_languages {
+                counts.retain(|(_, count)| *count >= min);
+                // Stable, so creators with the same count stay alphabetical
+                counts.sort_by_key(|(_, count)| Reverse(*count));
+            }
+
+            let mut table = Table::new();
+            table.add_row(numeric_header("Creator", "Languages"));
+            for (creator, count) in counts {
+                table.add_row(numeric_row(&creator, count));
+            }
+            output.print_table(&table);
+        }
+        Commands::Paradigms => {
+            let counts = kapa::paradigm_counts(&languages);
+
+            let mut table = Table::new();
+            table.add_row(numeric_header("Paradigm", "Languages"));
+            for (paradigm, count) in counts {
+                table.add_row(numeric_row(&paradigm, count));
+            }
+            output.print_table(&table);
+        }
+        Commands::Years => {
             let mut table = Table::new();
-            table.add_row(row![bFg=> "Paradigm", "Count"]);
-            for (paradigm, count) in paradigm_counts {
-                table.add_row(row![paradigm, count]);
+            table.add_row(Row::new(vec![
+                Cell::new("Year").style_spec("bFgr"),
+                Cell::new("Languages").style_spec("bFgr"),
+            ]));
+            for (year, langs) in kapa::group_by_year(&languages) {
+                table.add_row(Row::new(vec![
+                    Cell::new(&year.to_string()).style_spec("r"),
+                    Cell::new(&langs.len().to_string()).style_spec("r"),
+                ]));
             }
-            table.printstd();
+            output.print_table(&table);
         }
+        Commands::Filter { filter, listing } => {
+            let filtered = filter.to_filter().apply(&languages);
+            matched = print_listing(
+                filtered,
+                &listing,
+                &mut output,
+                "Languages matching all filters:",
+                "No languages match all filters",
+            );
+        }
+        Commands::Top { n } => {
+            let mut table = Table::new();
+            table.add_row(Row::new(vec![
+                Cell::new("Year").style_spec("bFgr"),
+                Cell::new("Languages").style_spec("bFgr"),
+                Cell::new("Names").style_spec("bFg"),
+            ]));
+            for (year, langs) in kapa::busiest_years(&languages).into_iter().take(n) {
+                let names: Vec<_> = langs.iter().map(|lang| lang.name.as_str()).collect();
+                table.add_row(Row::new(vec![
+                    Cell::new(&year.to_string()).style_spec("r"),
+                    Cell::new(&langs.len().to_string()).style_spec("r"),
+                    Cell::new(&names.join(", ")),
+                ]));
+            }
+            output.print_table(&table);
+        }
+        Commands::Ranking { limit } => {
+            let mut table = Table::new();
+            table.add_row(Row::new(vec![
+                Cell::new("Name").style_spec("bFg"),
+                Cell::new("Year").style_spec("bFgr"),
+                Cell::new("Influenced").style_spec("bFgr"),
+            ]));
+            for (lang, count) in kapa::influence_ranking(&languages)
+                .into_iter()
+                .take(limit.map_or(usize::MAX, NonZeroUsize::get))
+            {
+                table.add_row(Row::new(vec![
+                    Cell::new(&lang.name),
+                    Cell::new(&lang.year.to_string()).style_spec("r"),
+                    Cell::new(&count.to_string()).style_spec("r"),
+                ]));
+            }
+            output.print_table(&table);
+        }
+        Commands::Count { filter } => {
+            let count = filter.to_filter().apply(&languages).len();
+            matched = count > 0;
+            outln!(output, "{}", count);
+        }
+        Commands::Export {
+            target,
+            path,
+            standalone,
+        } => {
+            if standalone && !matches!(target, ExportFormat::Html) {
+                eprintln!("Error: --standalone only applies to html exports");
+                process::exit(EXIT_USAGE);
+            }
+
+            let all: Vec<_> = languages.iter().collect();
+            let rendered = match target {
+                ExportFormat::Csv => output::render_csv(&all),
+                ExportFormat::Html => output::render_html(&all, standalone),
+                ExportFormat::Json => serde_json::to_string_pretty(&languages)
+                    .map(|json| json + "\n")
+                    .expect("Failed to serialize languages"),
+                ExportFormat::Yaml => {
+                    serde_yaml::to_string(&languages).expect("Failed to serialize languages")
+                }
+            };
+
+            match path {
+                Some(path) => {
+                    if let Err(err) = fs::write(&path, rendered) {
+                        eprintln!("Error: could not write {}: {}", path.display(), err);
+                        process::exit(EXIT_FAILURE);
+                    }
+                    outln!(
+                        output,
+                        "Exported {} languages to {}",
+                        languages.len(),
+                        path.display()
+                    );
+                }
+                None => output.text(&rendered),
+            }
+        }
+        Commands::Graph { json } => {
+            let graph = kapa::Graph::build(&languages);
+
+            for (name, influence) in &graph.unresolved {
+                eprintln!(
+                    "Warning: {} is influenced by unknown language '{}'",
+                    name, influence
+                );
+            }
+            for cycle in graph.cycles() {
+                eprintln!(
+                    "Warning: influence cycle {} -> {}",
+                    cycle.join(" -> "),
+                    cycle[0]
+                );
+            }
+
+            if json {
+                let adjacency = serde_json::to_string_pretty(&graph.adjacency())
+                    .expect("Failed to serialize graph");
+                outln!(output, "{}", adjacency);
+            } else {
+                output.text(&graph.to_dot());
+            }
+        }
+        Commands::Tui => {
+            if !io::stdout().is_terminal() {
+                eprintln!("Error: the tui command needs an interactive terminal");
+                process::exit(EXIT_USAGE);
+            }
+
+            if let Err(err) = tui::run(&languages) {
+                eprintln!("Error: {}", err);
+                process::exit(EXIT_FAILURE);
+            }
+        }
+        Commands::Add {
+            name,
+            year,
+            creators,
+            paradigm,
+            typing,
+            influenced_by,
+            aliases,
+            description,
+            tags,
+        } => {
+            let lang = Language {
+                name,
+                year,
+                creators,
+                paradigm,
+                typing,
+                influenced_by,
+                aliases,
+                description,
+                tags,
+            };
+            let name = lang.name.clone();
+            let data_file = writable_data_file(&data_files);
+
+            if let Err(err) = kapa::add_language(&mut languages, lang) {
+                eprintln!("Error: {}", err);
+                process::exit(EXIT_USAGE);
+            }
+            save_dataset(data_file, options.format, &languages);
+
+            outln!(output, "Added {} to {}", name, data_file.display());
+        }
+        Commands::Remove { name, dry_run } => {
+            let data_file = writable_data_file(&data_files);
+            let removed = match kapa::remove_language(&mut languages, &name) {
+                Ok(removed) => removed,
+                Err(err) => {
+                    eprintln!("Error: {}", err);
+                    process::exit(EXIT_NO_MATCHES);
+                }
+            };
+
+            if dry_run {
+                outln!(
+                    output,
+                    "Would remove {} ({}) from {}",
+                    removed.name,
+                    removed.year,
+                    data_file.display()
+                );
+            } else {
+                save_dataset(data_file, options.format, &languages);
+                outln!(
+                    output,
+                    "Removed {} ({}) from {}",
+                    removed.name,
+                    removed.year,
+                    data_file.display()
+                );
+            }
+        }
+        Commands::Stats {
+            by_decade,
+            distribution,
+            count_paradigms_per_language,
+            sort_paradigms,
+            paradigm,
+            creator,
+        } => {
+            let subset = match (&paradigm, &creator) {
+                (Some(name), _) => kapa::by_paradigm(&languages, name),
+                (_, Some(name)) => kapa::by_creator(&languages, name),
+                (None, None) => languages.iter().collect(),
+            };
+            let (Some(earliest), Some(latest)) = (
+                kapa::earliest(subset.iter().copied()),
+                kapa::latest(subset.iter().copied()),
+            ) else {
+                match (paradigm, creator) {
+                    (Some(name), _) => {
+                        noteln!(output, "No languages found with paradigm '{}'", name)
+                    }
+                    (_, Some(name)) => noteln!(output, "No languages found created by '{}'", name),
+                    (None, None) => noteln!(output, "No languages in dataset"),
+                }
+                finish(output, output_path.as_deref());
+                process::exit(EXIT_NO_MATCHES);
+            };
+
+            match (&paradigm, &creator) {
+                (Some(name), _) => noteln!(output, "Statistics for paradigm '{}':", name),
+                (_, Some(name)) => noteln!(output, "Statistics for creator '{}':", name),
+                (None, None) => noteln!(output, "Programming Language Statistics:"),
+            }
+            outln!(output, "- Total languages: {}", subset.len());
+            outln!(
+                output,
+                "- Earliest language: {} ({})",
+                earliest.name,
+                earliest.year
+            );
+            outln!(
+                output,
+                "- Latest language: {} ({})",
+                latest.name,
+                latest.year
+            );
+            if creator.is_some() {
+                outln!(
+                    output,
+                    "- Year span: {}–{} ({} years)",
+                    earliest.year,
+                    latest.year,
+                    latest.year - earliest.year
+                );
+            }
+
+            let credited: usize = subset.iter().map(|lang| lang.creators.len()).sum();
+            outln!(
+                output,
+                "- Distinct creators: {}",
+                kapa::creator_counts(subset.iter().copied()).len()
+            );
+            outln!(
+                output,
+                "- Average creators per language: {:.2}",
+                credited as f64 / subset.len() as f64
+            );
+
+            outln!(output, "\nParadigm Counts:");
+            let mut table = Table::new();
+            table.add_row(numeric_header("Paradigm", "Count"));
+            let mut counts = kapa::paradigm_counts(subset.iter().copied());
+            if let CountOrder::Name = sort_paradigms {
+                counts.sort_by(|(a, _), (b, _)| a.cmp(b));
+            }
+            for (paradigm, count) in counts {
+                table.add_row(numeric_row(&paradigm, count));
+            }
+            output.print_table(&table);
+
+            outln!(output, "\nTyping Counts:");
+            let mut table = Table::new();
+            table.add_row(numeric_header("Typing", "Count"));
+            for (typing, count) in kapa::typing_counts(subset.iter().copied()) {
+                table.add_row(numeric_row(&typing, count));
+            }
+            output.print_table(&table);
+
+            let tags = kapa::tag_counts(subset.iter().copied());
+            if !tags.is_empty() {
+                outln!(output, "\nTag Counts:");
+                let mut table = Table::new();
+                table.add_row(numeric_header("Tag", "Count"));
+                for (tag, count) in tags {
+                    table.add_row(numeric_row(&tag, count));
+                }
+                output.print_table(&table);
+            }
+
+            outln!(output, "\nMost Influential Languages:");
+            let mut table = Table::new();
+            table.add_row(numeric_header("Language", "Influenced"));
+            for (lang, count) in kapa::influence_counts(subset.iter().copied())
+                .into_iter()
+                .take(10)
+            {
+                table.add_row(numeric_row(&lang.name, count));
+            }
+            output.print_table(&table);
+
+            if by_decade {
+                outln!(output, "\nLanguages by Decade:");
+                let mut table = Table::new();
+                table.add_row(numeric_header("Decade", "Count"));
+                for (decade, count) in kapa::decade_counts(subset.iter().copied()) {
+                    table.add_row(numeric_row(&format!("{}s", decade), count));
+                }
+                output.print_table(&table);
+            }
+
+            if count_paradigms_per_language {
+                outln!(output, "\nParadigms per Language:");
+                let mut table = Table::new();
+                table.add_row(Row::new(vec![
+                    Cell::new("Paradigms").style_spec("bFgr"),
+                    Cell::new("Languages").style_spec("bFgr"),
+                ]));
+                for (paradigms, count) in kapa::paradigms_per_language(subset.iter().copied()) {
+                    table.add_row(Row::new(vec![
+                        Cell::new(&paradigms.to_string()).style_spec("r"),
+                        Cell::new(&count.to_string()).style_spec("r"),
+                    ]));
+                }
+                output.print_table(&table);
+
+                let listed: usize = subset.iter().map(|lang| lang.paradigm.len()).sum();
+                outln!(
+                    output,
+                    "- Average paradigms per language: {:.2}",
+                    listed as f64 / subset.len() as f64
+                );
+            }
+
+            if distribution && let Some(years) = kapa::year_distribution(subset.iter().copied()) {
+                outln!(output, "\nYear Distribution:");
+                let mut table = Table::new();
+                table.add_row(numeric_header("Measure", "Year"));
+                table.add_row(numeric_row("25th percentile", format!("{:.1}", years.p25)));
+                table.add_row(numeric_row("Median", format!("{:.1}", years.median)));
+                table.add_row(numeric_row("75th percentile", format!("{:.1}", years.p75)));
+                table.add_row(numeric_row(
+                    "Standard deviation",
+                    format!("{:.1}", years.std_dev),
+                ));
+                output.print_table(&table);
+            }
+        }
+    }
+
+    finish(output, output_path.as_deref());
+    if !matched {
+        process::exit(EXIT_NO_MATCHES);
     }
 }
@@ -0,0 +1,766 @@
+use clap::ValueEnum;
+use kapa::{Language, Mark, Tree};
+use prettytable::{Cell, Row, Table, row};
+use regex::{Regex, RegexBuilder};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::HashMap,
+    env, fmt,
+    fs::File,
+    io::{self, IsTerminal, LineWriter, Write},
+    path::Path,
+    process,
+};
+
+/// Like `println!`, but writes a line to an [`Output`].
+macro_rules! outln {
+    ($output:expr) => {
+        $output.line(format_args!(""))
+    };
+    ($output:expr, $($arg:tt)*) => {
+        $output.line(format_args!($($arg)*))
+    };
+}
+
+/// Like [`outln!`], for descriptive lines (headings, empty-result messages)
+/// that `--quiet` leaves out.
+macro_rules! noteln {
+    ($output:expr, $($arg:tt)*) => {
+        $output.note(format_args!($($arg)*))
+    };
+}
+
+pub(crate) use {noteln, outln};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OutputFormat {
+    Table,
+    Json,
+    Jsonl,
+    Csv,
+    Tsv,
+    Markdown,
+}
+
+/// A field that can be shown as a column of the language table.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Column {
+    Name,
+    Year,
+    Creators,
+    Paradigm,
+    Typing,
+    InfluencedBy,
+    Age,
+    Influenced,
+    Description,
+    Tags,
+}
+
+impl Column {
+    pub const DEFAULT: [Column; 5] = [
+        Column::Name,
+        Column::Year,
+        Column::Creators,
+        Column::Paradigm,
+        Column::Typing,
+    ];
+
+    fn title(self) -> &'static str {
+        match self {
+            Column::Name => "Name",
+            Column::Year => "Year",
+            Column::Creators => "Creators",
+            Column::Paradigm => "Paradigm",
+            Column::Typing => "Typing",
+            Column::InfluencedBy => "Influenced By",
+            Column::Age => "Age",
+            Column::Influenced => "Influenced",
+            Column::Description => "Description",
+            Column::Tags => "Tags",
+        }
+    }
+
+    /// Whether the column holds numbers, which line up better right-aligned.
+    fn is_numeric(self) -> bool {
+        matches!(self, Column::Year | Column::Age | Column::Influenced)
+    }
+
+    /// The cell text for `lang`, with lists separated by commas.
+    pub fn text(self, lang: &Language, influence: &HashMap<String, usize>) -> String {
+        match self {
+            Column::Name => lang.name.clone(),
+            Column::Year => lang.year.to_string(),
+            Column::Creators => lang.creators.join(", "),
+            Column::Paradigm => lang.paradigm.join(", "),
+            Column::Typing => lang.typing.clone(),
+            Column::InfluencedBy => lang.influenced_by.join(", "),
+            // Years in the future count as brand new rather than negative
+            Column::Age => kapa::current_year().saturating_sub(lang.year).to_string(),
+            Column::Influenced => influence
+                .get(&lang.name.to_lowercase())
+                .copied()
+                .unwrap_or(0)
+                .to_string(),
+            Column::Description => lang.description.clone().unwrap_or_default(),
+            Column::Tags => lang.tags.join(", "),
+        }
+    }
+}
+
+/// A language as written in JSON output, with the computed fields added by
+/// `--enrich`.
+#[derive(Serialize)]
+struct JsonLanguage<'a> {
+    #[serde(flatten)]
+    language: &'a Language,
+    #[serde(flatten)]
+    computed: Option<Computed>,
+}
+
+#[derive(Serialize)]
+struct Computed {
+    age: u32,
+    influenced_count: usize,
+    paradigm_count: usize,
+}
+
+/// How results are rendered, and where they are written (stdout or an `--output` file).
+pub struct Output {
+    pub format: OutputFormat,
+    pub color: bool,
+    pub columns: Vec<Column>,
+    /// Longest cell text in language tables, in characters, before truncating.
+    pub max_width: Option<usize>,
+    /// How many languages each language influenced, keyed by lowercase name.
+    /// Only filled in when something needs it: the influenced column,
+    /// `--enrich` or `info --field influenced`.
+    pub influence: HashMap<String, usize>,
+    /// Leave out headings and other lines that only describe the results.
+    pub quiet: bool,
+    /// Print result lists one line per language, whatever the format.
+    pub summary: bool,
+    /// Add computed fields to each language in JSON output. Needs `influence`.
+    pub enrich: bool,
+    /// Matches text to emphasize in language table cells, when color is on.
+    pub highlight: Option<Regex>,
+    writer: Box<dyn Write>,
+    written: usize,
+}
+
+impl Output {
+    /// `color` forces table colors on or off when set; otherwise they're used
+    /// when stdout is a terminal. Output to a file is never colored.
+    pub fn new(
+        format: OutputFormat,
+        color: Option<bool>,
+        columns: Vec<Column>,
+        path: Option<&Path>,
+    ) -> io::Result<Self> {
+        // Line buffering keeps a file complete even when a command exits early
+        let writer: Box<dyn Write> = match path {
+            Some(path) => Box::new(LineWriter::new(File::create(path)?)),
+            None => Box::new(io::stdout()),
+        };
+
+        // Turning color off beats NO_COLOR, which beats forcing it on, which
+        // beats terminal detection
+        let color = color != Some(false)
+            && path.is_none()
+            && env::var_os("NO_COLOR").is_none_or(|value| value.is_empty())
+            && (color == Some(true) || io::stdout().is_terminal());
+
+        let columns = if columns.is_empty() {
+            Column::DEFAULT.to_vec()
+        } else {
+            columns
+        };
+
+        Ok(Output {
+            format,
+            color,
+            columns,
+            max_width: None,
+            influence: HashMap::new(),
+            quiet: false,
+            summary: false,
+            enrich: false,
+            highlight: None,
+            writer,
+            written: 0,
+        })
+    }
+
+    /// Emphasizes every occurrence of `term` in language tables, ignoring case.
+    pub fn set_highlight(&mut self, term: &str) {
+        self.highlight = Some(
+            RegexBuilder::new(&regex::escape(term))
+                .case_insensitive(true)
+                .build()
+                .expect("escaped pattern is always valid"),
+        );
+    }
+
+    /// Writes one line of output, exiting if the destination can't be written to.
+    pub fn line(&mut self, args: fmt::Arguments) {
+        let result = self.write_fmt(args).and_then(|()| self.write_all(b"\n"));
+        check(result);
+    }
+
+    /// Writes one descriptive line, unless `quiet` is set.
+    pub fn note(&mut self, args: fmt::Arguments) {
+        if !self.quiet {
+            self.line(args);
+        }
+    }
+
+    pub fn text(&mut self, text: &str) {
+        let result = self.write_all(text.as_bytes());
+        check(result);
+    }
+
+    /// Flushes the destination and returns the number of bytes written to it.
+    pub fn finish(mut self) -> usize {
+        let result = self.flush();
+        check(result);
+        self.written
+    }
+
+    pub fn print_table(&mut self, table: &Table) {
+        if self.color {
+            // Forced, since color may have been asked for without a terminal
+            let result = table.print_tty(true).map(|_| ());
+            check(result);
+        } else {
+            let result = table.print(self).map(|_| ());
+            check(result);
+        }
+    }
+
+    pub fn print_languages(&mut self, languages: &[&Language]) {
+        match self.format {
+            OutputFormat::Table => self.print_languages_table(languages),
+            OutputFormat::Json => self.print_languages_json(languages),
+            OutputFormat::Jsonl => self.print_languages_jsonl(languages),
+            OutputFormat::Csv => self.text(&render_csv(languages)),
+            OutputFormat::Tsv => self.print_languages_tsv(languages),
+            OutputFormat::Markdown => self.print_languages_markdown(languages),
+        }
+    }
+
+    // Headings and empty-result messages would corrupt JSON/CSV/Markdown output
+    pub fn print_results(&mut self, languages: &[&Language], heading: &str, empty: &str) {
+        if self.summary {
+            if languages.is_empty() {
+                noteln!(self, "{}", empty);
+                return;
+            }
+            noteln!(self, "{}", heading);
+            for lang in languages {
+                outln!(
+                    self,
+                    "{} ({}) — {} — {}",
+                    lang.name,
+                    lang.year,
+                    lang.typing,
+                    lang.paradigm.join(", ")
+                );
+            }
+            return;
+        }
+
+        if self.format == OutputFormat::Table {
+            if languages.is_empty() {
+                noteln!(self, "{}", empty);
+                return;
+            }
+            noteln!(self, "{}", heading);
+        }
+
+        self.print_languages(languages);
+    }
+
+    /// Prints every field of `lang`, plus the languages it influenced when
+    /// `influenced` is given.
+    pub fn print_language_detail(&mut self, lang: &Language, influenced: Option<&[&Language]>) {
+        let mut table = Table::new();
+
+        table.add_row(row![b->"Name", lang.name]);
+        table.add_row(row![b->"Year", lang.year]);
+        table.add_row(row![b->"Creators", lang.creators.join(", ")]);
+        table.add_row(row![b->"Paradigm", lang.paradigm.join(", ")]);
+        table.add_row(Row::new(vec![
+            Cell::new("Typing").style_spec("b"),
+            typing_cell(&lang.typing, &lang.typing),
+        ]));
+        table.add_row(row![b->"Influenced By", lang.influenced_by.join(", ")]);
+        if let Some(influenced) = influenced {
+            let names: Vec<_> = influenced.iter().map(|lang| lang.name.as_str()).collect();
+            table.add_row(row![b->"Influenced", names.join(", ")]);
+        }
+        if !lang.aliases.is_empty() {
+            table.add_row(row![b->"Also Known As", lang.aliases.join(", ")]);
+        }
+        if !lang.tags.is_empty() {
+            table.add_row(row![b->"Tags", lang.tags.join(", ")]);
+        }
+        if let Some(description) = &lang.description {
+            table.add_row(row![b->"Description", description]);
+        }
+
+        self.print_table(&table);
+    }
+
+    pub fn print_comparison(&mut self, first: &Language, second: &Language) {
+        let mut table = Table::new();
+
+        table.add_row(row![bFg=> "", first.name, second.name]);
+
+        let fields = [
+            ("Year", first.year.to_string(), second.year.to_string()),
+            (
+                "Creators",
+                first.creators.join(", "),
+                second.creators.join(", "),
+            ),
+            (
+                "Paradigm",
+                first.paradigm.join(", "),
+                second.paradigm.join(", "),
+            ),
+            ("Typing", first.typing.clone(), second.typing.clone()),
+            (
+                "Influenced By",
+                first.influenced_by.join(", "),
+                second.influenced_by.join(", "),
+            ),
+        ];
+
+        for (field, left, right) in fields {
+            if left == right {
+                table.add_row(row![b->field, left, right]);
+            } else {
+                table.add_row(row![bFy->field, Fy->left, Fy->right]);
+            }
+        }
+
+        self.print_table(&table);
+    }
+
+    pub fn print_scored_table(&mut self, matches: &[(&Language, usize)]) {
+        let mut table = Table::new();
+
+        let mut header = self.header_row();
+        header.add_cell(Cell::new("Distance").style_spec("bFgr"));
+        table.add_row(header);
+
+        for (lang, distance) in matches {
+            let mut row = self.language_row(lang);
+            row.add_cell(Cell::new(&distance.to_string()).style_spec("r"));
+            table.add_row(row);
+        }
+
+        self.print_table(&table);
+    }
+
+    fn print_languages_table(&mut self, languages: &[&Language]) {
+        let mut table = Table::new();
+
+        table.add_row(self.header_row());
+
+        for lang in languages {
+            table.add_row(self.language_row(lang));
+        }
+
+        self.print_table(&table);
+    }
+
+    /// Prints each group of languages under its own title. Machine-readable
+    /// formats keep the group name alongside its languages instead.
+    pub fn print_groups(&mut self, groups: &[(String, Vec<&Language>)]) {
+        #[derive(Serialize)]
+        struct Group<'a> {
+            group: &'a str,
+            #[serde(skip)]
+            languages: &'a [&'a Language],
+            #[serde(rename = "languages")]
+            json: Vec<JsonLanguage<'a>>,
+        }
+
+        let groups: Vec<_> = groups
+            .iter()
+            .map(|(group, languages)| Group {
+                group,
+                languages,
+                json: self.json_languages(languages),
+            })
+            .collect();
+
+        match self.format {
+            OutputFormat::Table => {
+                for (i, group) in groups.iter().enumerate() {
+                    if i > 0 {
+                        outln!(self);
+                    }
+                    outln!(self, "{} ({}):", group.group, group.languages.len());
+                    self.print_languages_table(group.languages);
+                }
+            }
+            OutputFormat::Json => {
+                let json =
+                    serde_json::to_string_pretty(&groups).expect("Failed to serialize languages");
+                outln!(self, "{}", json);
+            }
+            OutputFormat::Jsonl => {
+                for group in &groups {
+                    let json = serde_json::to_string(group).expect("Failed to serialize languages");
+                    outln!(self, "{}", json);
+                }
+            }
+            OutputFormat::Csv => {
+                let mut writer = csv::Writer::from_writer(Vec::new());
+                let header = ["group"].into_iter().chain(CSV_HEADER);
+                writer
+                    .write_record(header)
+                    .expect("Failed to serialize languages");
+                for group in &groups {
+                    for lang in group.languages {
+                        let record = [group.group.to_string()]
+                            .into_iter()
+                            .chain(csv_record(lang));
+                        writer
+                            .write_record(record)
+                            .expect("Failed to serialize languages");
+                    }
+                }
+                self.text(&finish_csv(writer));
+            }
+            OutputFormat::Tsv => {
+                outln!(self, "group\t{}", CSV_HEADER.join("\t"));
+                for group in &groups {
+                    self.print_tsv_rows(group.languages, Some(group.group));
+                }
+            }
+            OutputFormat::Markdown => {
+                for (i, group) in groups.iter().enumerate() {
+                    if i > 0 {
+                        outln!(self);
+                    }
+                    outln!(self, "### {}", escape_markdown(group.group));
+                    outln!(self);
+                    self.print_languages_markdown(group.languages);
+                }
+            }
+        }
+    }
+
+    /// Prints languages, which must already be sorted by year, as a vertical
+    /// timeline with one marker per year.
+    pub fn print_timeline(&mut self, languages: &[&Language]) {
+        let mut previous = None;
+
+        for lang in languages {
+            if previous == Some(lang.year) {
+                outln!(self, "     |-- {}", lang.name);
+                continue;
+            }
+
+            if previous.is_some() {
+                outln!(self, "     |");
+            }
+            outln!(self, "{:>4} *-- {}", lang.year, lang.name);
+            previous = Some(lang.year);
+        }
+    }
+
+    /// Prints an influence tree with one language per line, indented by depth.
+    pub fn print_tree(&mut self, tree: &Tree) {
+        outln!(self, "{}", tree.name);
+        self.print_subtrees(&tree.children, "");
+    }
+
+    fn print_subtrees(&mut self, trees: &[Tree], prefix: &str) {
+        for (i, tree) in trees.iter().enumerate() {
+            let last = i + 1 == trees.len();
+            let note = match tree.mark {
+                Mark::None => "",
+                Mark::Unknown => " (not in dataset)",
+                Mark::Repeated => " (*)",
+                Mark::Cycle => " (cycle)",
+                Mark::Truncated => " (truncated)",
+            };
+            let branch = if last { "└── " } else { "├── " };
+            outln!(self, "{}{}{}{}", prefix, branch, tree.name, note);
+
+            let indent = if last { "    " } else { "│   " };
+            self.print_subtrees(&tree.children, &format!("{}{}", prefix, indent));
+        }
+    }
+
+    fn json_languages<'a>(&self, languages: &[&'a Language]) -> Vec<JsonLanguage<'a>> {
+        languages
+            .iter()
+            .map(|lang| JsonLanguage {
+                language: lang,
+                computed: self.enrich.then(|| Computed {
+                    age: kapa::current_year().saturating_sub(lang.year),
+                    influenced_count: self
+                        .influence
+                        .get(&lang.name.to_lowercase())
+                        .copied()
+                        .unwrap_or(0),
+                    paradigm_count: lang.paradigm.len(),
+                }),
+            })
+            .collect()
+    }
+
+    fn print_languages_json(&mut self, languages: &[&Language]) {
+        let json = serde_json::to_string_pretty(&self.json_languages(languages))
+            .expect("Failed to serialize languages");
+        outln!(self, "{}", json);
+    }
+
+    fn print_languages_jsonl(&mut self, languages: &[&Language]) {
+        for lang in self.json_languages(languages) {
+            let json = serde_json::to_string(&lang).expect("Failed to serialize language");
+            outln!(self, "{}", json);
+        }
+    }
+
+    fn print_languages_tsv(&mut self, languages: &[&Language]) {
+        outln!(self, "{}", CSV_HEADER.join("\t"));
+        self.print_tsv_rows(languages, None);
+    }
+
+    // Tabs and line breaks inside values would split fields and rows, so
+    // they become spaces
+    fn print_tsv_rows(&mut self, languages: &[&Language], group: Option<&str>) {
+        for lang in languages {
+            let fields: Vec<_> = group
+                .map(str::to_string)
+                .into_iter()
+                .chain(csv_record(lang))
+                .map(|field| field.replace(['\t', '\n', '\r'], " "))
+                .collect();
+            outln!(self, "{}", fields.join("\t"));
+        }
+    }
+
+    fn print_languages_markdown(&mut self, languages: &[&Language]) {
+        let titles: Vec<_> = self.columns.iter().map(|column| column.title()).collect();
+        let rules: Vec<_> = self
+            .columns
+            .iter()
+            .map(|column| if column.is_numeric() { "---:" } else { "---" })
+            .collect();
+        outln!(self, "| {} |", titles.join(" | "));
+        outln!(self, "| {} |", rules.join(" | "));
+
+        for lang in languages {
+            let cells: Vec<_> = self
+                .columns
+                .iter()
+                .map(|column| escape_markdown(&column.text(lang, &self.influence)))
+                .collect();
+            outln!(self, "| {} |", cells.join(" | "));
+        }
+    }
+
+    fn header_row(&self) -> Row {
+        Row::new(
+            self.columns
+                .iter()
+                .map(|column| {
+                    let spec = if column.is_numeric() { "bFgr" } else { "bFg" };
+                    Cell::new(column.title()).style_spec(spec)
+                })
+                .collect(),
+        )
+    }
+
+    fn language_row(&self, lang: &Language) -> Row {
+        Row::new(
+            self.columns
+                .iter()
+                .map(|&column| {
+                    let text = truncate(column.text(lang, &self.influence), self.max_width);
+                    let text = match &self.highlight {
+                        // Bold and underline, switched off without resetting
+                        // the cell's own color
+                        Some(regex) if self.color => regex
+                            .replace_all(&text, "\x1b[1;4m$0\x1b[22;24m")
+                            .into_owned(),
+                        _ => text,
+                    };
+                    match column {
+                        Column::Typing => typing_cell(&text, &lang.typing),
+                        _ if column.is_numeric() => Cell::new(&text).style_spec("r"),
+                        _ => Cell::new(&text),
+                    }
+                })
+                .collect(),
+        )
+    }
+}
+
+impl Write for Output {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let written = self.writer.write(buf)?;
+        self.written += written;
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.writer.flush()
+    }
+}
+
+fn check(result: io::Result<()>) {
+    if let Err(err) = result {
+        // The reader went away (e.g. `kapa list | head`), so there's nobody left to tell
+        if err.kind() == io::ErrorKind::BrokenPipe {
+            process::exit(0);
+        }
+        eprintln!("Error: could not write output: {}", err);
+        process::exit(crate::EXIT_FAILURE);
+    }
+}
+
+// Styled by the full typing so a truncated cell keeps its color
+fn typing_cell(text: &str, typing: &str) -> Cell {
+    let cell = Cell::new(text);
+    let typing = typing.to_lowercase();
+
+    if typing.contains("static") {
+        cell.style_spec("Fg")
+    } else if typing.contains("dynamic") {
+        cell.style_spec("Fy")
+    } else {
+        cell
+    }
+}
+
+const CSV_HEADER: [&str; 6] = [
+    "name",
+    "year",
+    "creators",
+    "paradigm",
+    "typing",
+    "influenced_by",
+];
+
+/// Renders the languages as RFC 4180 CSV with a header row. List fields are
+/// joined with `;` into a single cell.
+pub fn render_csv(languages: &[&Language]) -> String {
+    let mut writer = csv::Writer::from_writer(Vec::new());
+    writer
+        .write_record(CSV_HEADER)
+        .expect("Failed to serialize languages");
+    for lang in languages {
+        writer
+            .write_record(csv_record(lang))
+            .expect("Failed to serialize languages");
+    }
+    finish_csv(writer)
+}
+
+fn csv_record(lang: &Language) -> [String; 6] {
+    [
+        lang.name.clone(),
+        lang.year.to_string(),
+        lang.creators.join(";"),
+        lang.paradigm.join(";"),
+        lang.typing.clone(),
+        lang.influenced_by.join(";"),
+    ]
+}
+
+fn finish_csv(writer: csv::Writer<Vec<u8>>) -> String {
+    let bytes = writer.into_inner().expect("Failed to serialize languages");
+    String::from_utf8(bytes).expect("CSV output is built from strings")
+}
+
+/// Renders the languages as an HTML `<table>`, or as a whole page around it
+/// when `standalone` is set.
+pub fn render_html(languages: &[&Language], standalone: bool) -> String {
+    let mut html = String::new();
+
+    if standalone {
+        html.push_str(concat!(
+            "<!DOCTYPE html>\n",
+            "<html lang=\"en\">\n",
+            "<head>\n",
+            "<meta charset=\"utf-8\">\n",
+            "<title>Programming Languages</title>\n",
+            "<style>\n",
+            "body { font-family: sans-serif; margin: 2em; }\n",
+            "table { border-collapse: collapse; }\n",
+            "th, td { border: 1px solid #ccc; padding: 0.4em 0.8em; text-align: left; }\n",
+            "th { background: #f3f3f3; }\n",
+            "</style>\n",
+            "</head>\n",
+            "<body>\n",
+        ));
+    }
+
+    html.push_str("<table>\n<thead>\n<tr>");
+    for title in [
+        "Name",
+        "Year",
+        "Creators",
+        "Paradigm",
+        "Typing",
+        "Influenced By",
+    ] {
+        html.push_str(&format!("<th>{}</th>", title));
+    }
+    html.push_str("</tr>\n</thead>\n<tbody>\n");
+
+    for lang in languages {
+        let cells = [
+            lang.name.clone(),
+            lang.year.to_string(),
+            lang.creators.join(", "),
+            lang.paradigm.join(", "),
+            lang.typing.clone(),
+            lang.influenced_by.join(", "),
+        ];
+        html.push_str("<tr>");
+        for cell in cells {
+            html.push_str(&format!("<td>{}</td>", escape_html(&cell)));
+        }
+        html.push_str("</tr>\n");
+    }
+    html.push_str("</tbody>\n</table>\n");
+
+    if standalone {
+        html.push_str("</body>\n</html>\n");
+    }
+
+    html
+}
+
+fn truncate(text: String, max_width: Option<usize>) -> String {
+    match max_width {
+        Some(width) if text.chars().count() > width => {
+            let mut truncated: String = text.chars().take(width.saturating_sub(1)).collect();
+            truncated.push('…');
+            truncated
+        }
+        _ => text,
+    }
+}
+
+fn escape_markdown(cell: &str) -> String {
+    cell.replace('|', "\\|")
+}
+
+fn escape_html(cell: &str) -> String {
+    cell.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&#39;")
+}
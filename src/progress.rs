@@ -0,0 +1,37 @@
+use indicatif::{HumanBytes, ProgressBar, ProgressStyle};
+use std::{
+    fs,
+    io::{self, IsTerminal},
+    path::PathBuf,
+    time::Duration,
+};
+
+// Smaller datasets load before a progress bar would even be seen
+const THRESHOLD: u64 = 2 * 1024 * 1024;
+
+/// A bar counting `paths` as they load, drawn on stderr when the files add up
+/// to at least [`THRESHOLD`] bytes and stderr is a terminal, and hidden
+/// otherwise.
+pub fn loading(paths: &[PathBuf]) -> ProgressBar {
+    let size: u64 = paths
+        .iter()
+        .filter_map(|path| fs::metadata(path).ok())
+        .map(|metadata| metadata.len())
+        .sum();
+    if size < THRESHOLD || !io::stderr().is_terminal() {
+        return ProgressBar::hidden();
+    }
+
+    let bar = ProgressBar::new(paths.len() as u64)
+        .with_style(
+            ProgressStyle::with_template(
+                "{spinner} Loading {msg} [{bar:30}] {pos}/{len} files ({elapsed})",
+            )
+            .unwrap()
+            .progress_chars("=> "),
+        )
+        .with_message(HumanBytes(size).to_string());
+    // Parsing one file can take a while, so keep the elapsed time moving
+    bar.enable_steady_tick(Duration::from_millis(100));
+    bar
+}
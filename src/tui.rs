@@ -0,0 +1,184 @@
+use kapa::Language;
+use ratatui::{
+    DefaultTerminal, Frame,
+    crossterm::event::{self, Event, KeyCode, KeyEventKind, KeyModifiers},
+    layout::{Constraint, Layout, Rect},
+    style::{Color, Style, Stylize},
+    text::{Line, Span},
+    widgets::{Block, List, ListState, Paragraph, Wrap},
+};
+use std::io;
+
+struct App<'a> {
+    languages: &'a [Language],
+    filtered: Vec<&'a Language>,
+    query: String,
+    searching: bool,
+    list: ListState,
+}
+
+impl<'a> App<'a> {
+    fn new(languages: &'a [Language]) -> Self {
+        let mut app = App {
+            languages,
+            filtered: Vec::new(),
+            query: String::new(),
+            searching: false,
+            list: ListState::default(),
+        };
+        app.refilter();
+        app
+    }
+
+    // Uses the same matching as `kapa search`, so an empty query shows everything
+    fn refilter(&mut self) {
+        self.filtered = kapa::search(self.languages, &self.query);
+        self.list.select(if self.filtered.is_empty() {
+            None
+        } else {
+            Some(0)
+        });
+    }
+
+    fn selected(&self) -> Option<&'a Language> {
+        self.list
+            .selected()
+            .and_then(|index| self.filtered.get(index).copied())
+    }
+
+    /// Handles one key press, returning false once the user asks to quit.
+    fn handle_key(&mut self, code: KeyCode, modifiers: KeyModifiers) -> bool {
+        if code == KeyCode::Char('c') && modifiers.contains(KeyModifiers::CONTROL) {
+            return false;
+        }
+
+        match code {
+            KeyCode::Up => self.list.select_previous(),
+            KeyCode::Down => self.list.select_next(),
+            KeyCode::Enter | KeyCode::Esc if self.searching => self.searching = false,
+            KeyCode::Backspace if self.searching => {
+                self.query.pop();
+                self.refilter();
+            }
+            KeyCode::Char(c) if self.searching => {
+                self.query.push(c);
+                self.refilter();
+            }
+            KeyCode::Char('/') => self.searching = true,
+            KeyCode::Char('q') => return false,
+            _ => {}
+        }
+
+        true
+    }
+
+    fn draw(&mut self, frame: &mut Frame) {
+        let [search, body, help] = Layout::vertical([
+            Constraint::Length(3),
+            Constraint::Min(0),
+            Constraint::Length(1),
+        ])
+        .areas(frame.area());
+        let [list, detail] =
+            Layout::horizontal([Constraint::Percentage(40), Constraint::Percentage(60)])
+                .areas(body);
+
+        self.draw_search(frame, search);
+        self.draw_list(frame, list);
+        self.draw_detail(frame, detail);
+
+        let hint = if self.searching {
+            "type to filter · Enter/Esc: done · ↑/↓: move"
+        } else {
+            "↑/↓: move · /: search · q: quit"
+        };
+        frame.render_widget(Line::from(hint).dark_gray(), help);
+    }
+
+    fn draw_search(&self, frame: &mut Frame, area: Rect) {
+        let border = if self.searching {
+            Style::new().yellow()
+        } else {
+            Style::new()
+        };
+        let block = Block::bordered().title(" Search ").border_style(border);
+        frame.render_widget(Paragraph::new(self.query.as_str()).block(block), area);
+
+        if self.searching {
+            let offset = self.query.chars().count() as u16;
+            frame.set_cursor_position((area.x + 1 + offset, area.y + 1));
+        }
+    }
+
+    fn draw_list(&mut self, frame: &mut Frame, area: Rect) {
+        let items: Vec<_> = self
+            .filtered
+            .iter()
+            .map(|lang| format!("{} ({})", lang.name, lang.year))
+            .collect();
+        let title = format!(" Languages ({}/{}) ", items.len(), self.languages.len());
+
+        let list = List::new(items)
+            .block(Block::bordered().title(title))
+            .highlight_style(Style::new().reversed())
+            .highlight_symbol("> ");
+        frame.render_stateful_widget(list, area, &mut self.list);
+    }
+
+    fn draw_detail(&self, frame: &mut Frame, area: Rect) {
+        let block = Block::bordered().title(" Details ");
+        let Some(lang) = self.selected() else {
+            frame.render_widget(Paragraph::new("No languages match").block(block), area);
+            return;
+        };
+
+        let typing = lang.typing.to_lowercase();
+        let typing_color = if typing.contains("static") {
+            Color::Green
+        } else if typing.contains("dynamic") {
+            Color::Yellow
+        } else {
+            Color::Reset
+        };
+
+        let field = |name: &'static str, value: Span<'a>| {
+            Line::from(vec![Span::from(format!("{:<15}", name)).bold(), value])
+        };
+        let mut lines = vec![
+            field("Name", Span::from(lang.name.as_str())),
+            field("Year", Span::from(lang.year.to_string())),
+            field("Creators", Span::from(lang.creators.join(", "))),
+            field("Paradigm", Span::from(lang.paradigm.join(", "))),
+            field("Typing", Span::from(lang.typing.as_str()).fg(typing_color)),
+            field("Influenced By", Span::from(lang.influenced_by.join(", "))),
+        ];
+        if !lang.tags.is_empty() {
+            lines.push(field("Tags", Span::from(lang.tags.join(", "))));
+        }
+        if let Some(description) = &lang.description {
+            lines.push(Line::default());
+            lines.push(Line::from(description.as_str()));
+        }
+
+        let paragraph = Paragraph::new(lines).block(block).wrap(Wrap { trim: true });
+        frame.render_widget(paragraph, area);
+    }
+}
+
+/// Runs the interactive browser until the user quits.
+pub fn run(languages: &[Language]) -> io::Result<()> {
+    ratatui::run(|terminal| event_loop(terminal, App::new(languages)))
+}
+
+fn event_loop(terminal: &mut DefaultTerminal, mut app: App) -> io::Result<()> {
+    loop {
+        terminal.draw(|frame| app.draw(frame))?;
+
+        if let Event::Key(key) = event::read()?
+            && key.kind == KeyEventKind::Press
+            && !app.handle_key(key.code, key.modifiers)
+        {
+            return Ok(());
+        }
+    }
+}
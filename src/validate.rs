@@ -0,0 +1,73 @@
+use crate::{Language, current_year};
+use std::{collections::HashSet, fmt};
+
+/// A structural problem found in a dataset.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Problem {
+    DuplicateName(String),
+    MissingYear(String),
+    FutureYear { name: String, year: u32 },
+    NoCreators(String),
+    UnknownInfluence { name: String, influence: String },
+}
+
+impl fmt::Display for Problem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Problem::DuplicateName(name) => write!(f, "{}: duplicate language name", name),
+            Problem::MissingYear(name) => write!(f, "{}: year is missing (0)", name),
+            Problem::FutureYear { name, year } => {
+                write!(f, "{}: year {} is in the future", name, year)
+            }
+            Problem::NoCreators(name) => write!(f, "{}: no creators listed", name),
+            Problem::UnknownInfluence { name, influence } => write!(
+                f,
+                "{}: influenced_by references unknown language '{}'",
+                name, influence
+            ),
+        }
+    }
+}
+
+/// Checks the dataset for duplicate names, implausible years, missing creators
+/// and `influenced_by` entries that don't name a known language.
+pub fn validate(languages: &[Language]) -> Vec<Problem> {
+    let this_year = current_year();
+    let known: HashSet<_> = languages
+        .iter()
+        .map(|lang| lang.name.to_lowercase())
+        .collect();
+
+    let mut seen = HashSet::new();
+    let mut problems = Vec::new();
+
+    for lang in languages {
+        if !seen.insert(lang.name.to_lowercase()) {
+            problems.push(Problem::DuplicateName(lang.name.clone()));
+        }
+
+        if lang.year == 0 {
+            problems.push(Problem::MissingYear(lang.name.clone()));
+        } else if lang.year > this_year {
+            problems.push(Problem::FutureYear {
+                name: lang.name.clone(),
+                year: lang.year,
+            });
+        }
+
+        if lang.creators.is_empty() {
+            problems.push(Problem::NoCreators(lang.name.clone()));
+        }
+
+        for influence in &lang.influenced_by {
+            if !known.contains(&influence.to_lowercase()) {
+                problems.push(Problem::UnknownInfluence {
+                    name: lang.name.clone(),
+                    influence: influence.clone(),
+                });
+            }
+        }
+    }
+
+    problems
+}
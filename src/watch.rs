@@ -0,0 +1,91 @@
+use notify::{Event, RecursiveMode, Watcher};
+use std::{
+    env,
+    ffi::OsString,
+    fs,
+    io::{self, Write},
+    path::{Path, PathBuf},
+    process::{self, Command},
+    sync::mpsc::{self, Receiver},
+    thread,
+    time::Duration,
+};
+
+/// Clears the screen and re-runs kapa, minus `--watch`, every time one of
+/// `paths` changes. Only returns by exiting, usually on Ctrl-C.
+pub fn run(paths: &[PathBuf]) -> ! {
+    if paths.is_empty() || paths.iter().any(|path| path == Path::new("-")) {
+        eprintln!("Error: --watch needs a data file to watch, pass --data-file to choose one");
+        process::exit(crate::EXIT_USAGE);
+    }
+
+    let targets: Vec<PathBuf> = paths
+        .iter()
+        .map(|path| fs::canonicalize(path).unwrap_or_else(|_| path.clone()))
+        .collect();
+
+    let (sender, events) = mpsc::channel();
+    let mut watcher = match notify::recommended_watcher(sender) {
+        Ok(watcher) => watcher,
+        Err(err) => {
+            eprintln!("Error: could not watch for changes: {}", err);
+            process::exit(crate::EXIT_FAILURE);
+        }
+    };
+
+    // Editors often save by replacing the file, which would end a watch on the
+    // file itself, so watch the directories containing the files instead
+    for target in &targets {
+        let dir = target.parent().unwrap_or(Path::new("."));
+        if let Err(err) = watcher.watch(dir, RecursiveMode::NonRecursive) {
+            eprintln!("Error: could not watch {}: {}", dir.display(), err);
+            process::exit(crate::EXIT_FAILURE);
+        }
+    }
+
+    let exe = match env::current_exe() {
+        Ok(exe) => exe,
+        Err(err) => {
+            eprintln!("Error: could not find the kapa executable: {}", err);
+            process::exit(crate::EXIT_FAILURE);
+        }
+    };
+    let args: Vec<OsString> = env::args_os()
+        .skip(1)
+        .filter(|arg| arg != "--watch")
+        .collect();
+
+    loop {
+        print!("\x1b[2J\x1b[H");
+        let _ = io::stdout().flush();
+
+        // The child reports its own errors, so a broken file just waits for the next save
+        if let Err(err) = Command::new(&exe).args(&args).status() {
+            eprintln!("Error: could not run {}: {}", exe.display(), err);
+            process::exit(crate::EXIT_FAILURE);
+        }
+
+        wait_for_change(&events, &targets);
+    }
+}
+
+fn wait_for_change(events: &Receiver<notify::Result<Event>>, targets: &[PathBuf]) {
+    loop {
+        match events.recv() {
+            // Reads (including the child's own) show up as access events
+            Ok(Ok(event))
+                if !event.kind.is_access()
+                    && event.paths.iter().any(|path| targets.contains(path)) =>
+            {
+                break;
+            }
+            Ok(Ok(_)) => {}
+            Ok(Err(err)) => eprintln!("Warning: {}", err),
+            Err(_) => process::exit(crate::EXIT_FAILURE),
+        }
+    }
+
+    // A single save usually produces a burst of events; let it settle first
+    thread::sleep(Duration::from_millis(100));
+    while events.try_recv().is_ok() {}
+}
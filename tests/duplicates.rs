@@ -0,0 +1,58 @@
+use std::{fs, path::PathBuf, process::Command};
+
+const DATA: &str = r#"[
+  {"name": "Foo", "year": 1990, "creators": [], "paradigm": [], "typing": "static", "influenced_by": []},
+  {"name": "foo", "year": 1991, "creators": [], "paradigm": [], "typing": "dynamic", "influenced_by": []}
+]"#;
+
+// Each test gets its own copy, since `add` rewrites the file
+fn data_file(name: &str) -> PathBuf {
+    let path = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join(name);
+    fs::write(&path, DATA).unwrap();
+    path
+}
+
+fn kapa(data_file: &PathBuf, args: &[&str]) -> std::process::Output {
+    Command::new(env!("CARGO_BIN_EXE_kapa"))
+        .arg("--data-file")
+        .arg(data_file)
+        .arg("--no-cache")
+        .args(args)
+        .env("XDG_CONFIG_HOME", env!("CARGO_TARGET_TMPDIR"))
+        .env("HOME", env!("CARGO_TARGET_TMPDIR"))
+        .output()
+        .unwrap()
+}
+
+#[test]
+fn validate_reports_duplicates_within_one_file() {
+    let path = data_file("duplicates-validate.json");
+    let output = kapa(&path, &["validate"]);
+
+    assert!(!output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(
+        stdout.contains("foo: duplicate language name"),
+        "{}",
+        stdout
+    );
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(!stderr.contains("overrides"), "{}", stderr);
+}
+
+#[test]
+fn add_keeps_duplicates_within_one_file() {
+    let path = data_file("duplicates-add.json");
+    let output = kapa(
+        &path,
+        &[
+            "add", "--name", "Bar", "--year", "2000", "--typing", "static",
+        ],
+    );
+    assert!(output.status.success());
+
+    let languages: Vec<kapa::Language> =
+        serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+    let names: Vec<_> = languages.iter().map(|lang| lang.name.as_str()).collect();
+    assert_eq!(names, ["Foo", "foo", "Bar"]);
+}
@@ -0,0 +1,63 @@
+use kapa::{Language, Mark, Tree};
+
+fn language(name: &str, influenced_by: Vec<String>) -> Language {
+    Language {
+        name: name.to_string(),
+        year: 1950,
+        creators: Vec::new(),
+        paradigm: Vec::new(),
+        typing: "static".to_string(),
+        influenced_by,
+        aliases: Vec::new(),
+        description: None,
+        tags: Vec::new(),
+    }
+}
+
+// Each language is influenced by the one before it
+fn chain(len: usize) -> Vec<Language> {
+    (0..len)
+        .map(|i| {
+            let influenced_by = i.checked_sub(1).map(|j| format!("L{}", j));
+            language(&format!("L{}", i), influenced_by.into_iter().collect())
+        })
+        .collect()
+}
+
+// The deepest node, and how many levels down it is
+fn deepest<'a>(mut tree: &'a Tree<'a>) -> (&'a Tree<'a>, usize) {
+    let mut depth = 0;
+    while let Some(child) = tree.children.first() {
+        tree = child;
+        depth += 1;
+    }
+    (tree, depth)
+}
+
+#[test]
+fn ancestry_of_a_long_chain_is_truncated() {
+    let languages = chain(200_000);
+    let tree = kapa::ancestry(&languages, languages.last().unwrap());
+
+    let (leaf, depth) = deepest(&tree);
+    assert_eq!(leaf.mark, Mark::Truncated);
+    assert!(depth < languages.len());
+}
+
+#[test]
+fn descendants_of_a_long_chain_are_truncated() {
+    let languages = chain(20_000);
+    let tree = kapa::descendants(&languages, &languages[0]);
+
+    let (leaf, depth) = deepest(&tree);
+    assert_eq!(leaf.mark, Mark::Truncated);
+    assert!(depth < languages.len());
+}
+
+#[test]
+fn trees_are_rooted_at_the_given_language() {
+    let languages = vec![language("A", Vec::new()), language("a", Vec::new())];
+
+    assert_eq!(kapa::ancestry(&languages, &languages[0]).name, "A");
+    assert_eq!(kapa::descendants(&languages, &languages[0]).name, "A");
+}
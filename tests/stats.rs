@@ -0,0 +1,120 @@
+use kapa::Language;
+use std::process::Command;
+
+const DATA: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/data/stats.json");
+
+fn languages() -> Vec<Language> {
+    serde_json::from_str(&std::fs::read_to_string(DATA).unwrap()).unwrap()
+}
+
+fn stats(args: &[&str]) -> String {
+    let output = Command::new(env!("CARGO_BIN_EXE_kapa"))
+        .args(["--data-file", DATA, "--no-color", "--no-cache", "stats"])
+        .args(args)
+        // Keep a user config file from changing the output
+        .env("XDG_CONFIG_HOME", env!("CARGO_TARGET_TMPDIR"))
+        .env("HOME", env!("CARGO_TARGET_TMPDIR"))
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    String::from_utf8(output.stdout).unwrap()
+}
+
+#[test]
+fn earliest_and_latest_break_ties_by_name() {
+    let languages = languages();
+    assert_eq!(kapa::earliest(&languages).unwrap().name, "Alpha");
+    assert_eq!(kapa::latest(&languages).unwrap().name, "Beta");
+
+    let reversed: Vec<_> = languages.iter().rev().collect();
+    assert_eq!(
+        kapa::earliest(reversed.iter().copied()).unwrap().name,
+        "Alpha"
+    );
+    assert_eq!(kapa::latest(reversed.iter().copied()).unwrap().name, "Beta");
+}
+
+#[test]
+fn counts_are_sorted() {
+    let languages = languages();
+    assert_eq!(
+        kapa::paradigm_counts(&languages),
+        [
+            ("functional".to_string(), 2),
+            ("imperative".to_string(), 2),
+            ("object-oriented".to_string(), 2),
+        ]
+    );
+    assert_eq!(
+        kapa::typing_counts(&languages),
+        [("dynamic".to_string(), 2), ("static".to_string(), 2)]
+    );
+    assert_eq!(
+        kapa::creator_counts(&languages),
+        [
+            ("Ann Lee".to_string(), 2),
+            ("Bob Stone".to_string(), 2),
+            ("Cy Park".to_string(), 1),
+        ]
+    );
+
+    let influence: Vec<_> = kapa::influence_counts(&languages)
+        .into_iter()
+        .map(|(lang, count)| (lang.name.as_str(), count))
+        .collect();
+    assert_eq!(influence, [("Alpha", 2), ("Zeta", 2)]);
+}
+
+#[test]
+fn stats_output_is_stable() {
+    let expected = "\
+Programming Language Statistics:
+- Total languages: 4
+- Earliest language: Alpha (1970)
+- Latest language: Beta (1995)
+- Distinct creators: 3
+- Average creators per language: 1.25
+
+Paradigm Counts:
++-----------------+-------+
+| Paradigm        | Count |
++-----------------+-------+
+| functional      |     2 |
++-----------------+-------+
+| imperative      |     2 |
++-----------------+-------+
+| object-oriented |     2 |
++-----------------+-------+
+
+Typing Counts:
++---------+-------+
+| Typing  | Count |
++---------+-------+
+| dynamic |     2 |
++---------+-------+
+| static  |     2 |
++---------+-------+
+
+Most Influential Languages:
++----------+------------+
+| Language | Influenced |
++----------+------------+
+| Alpha    |          2 |
++----------+------------+
+| Zeta     |          2 |
++----------+------------+
+
+Languages by Decade:
++--------+-------+
+| Decade | Count |
++--------+-------+
+| 1970s  |     2 |
++--------+-------+
+| 1990s  |     2 |
++--------+-------+
+";
+
+    for _ in 0..3 {
+        assert_eq!(stats(&["--by-decade"]), expected);
+    }
+}